@@ -1,24 +1,198 @@
-use std::collections::HashSet;
+use std::{
+  collections::{hash_map::DefaultHasher, HashMap, HashSet},
+  hash::{Hash, Hasher},
+  sync::{Arc, Mutex},
+  time::{Duration, Instant},
+};
 
-use crate::adb::{command::run_device, error::Result, AppInfo};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 
-pub fn list_apps(device_id: &str, keyword: Option<&str>) -> Result<Vec<AppInfo>> {
-  let keyword = keyword.map(|k| k.to_ascii_lowercase());
+use crate::adb::{
+  command::run_device,
+  error::{AdbError, InstallFailure, Result},
+  AppInfo, AppOpInfo, ForegroundApp, PermissionInfo,
+};
+
+// 在此时间内的重复查询不再检查设备，直接使用缓存
+const APP_CACHE_TTL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone)]
+struct AppCacheEntry {
+  apps: Arc<Vec<AppInfo>>,
+  fingerprint: Option<String>, // 包名集合的指纹，见 package_fingerprint
+  checked_at: Instant,
+}
+
+// 按 设备:用户 缓存完整应用列表，过滤在本地完成
+static APP_CACHE: Lazy<Mutex<HashMap<String, AppCacheEntry>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 按 设备:包名 记录应用名。pm 不输出应用名，只能在提取图标时从 APK 清单读出
+static APP_LABELS: Lazy<Mutex<HashMap<String, String>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppKind {
+  #[default]
+  All,
+  System,
+  ThirdParty,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AppFilter {
+  /// 包名或已知的应用名包含关键字（不区分大小写）
+  #[serde(default)]
+  pub keyword: Option<String>,
+  /// 包名前缀，例如 com.tencent.
+  #[serde(default)]
+  pub prefix: Option<String>,
+  #[serde(default)]
+  pub kind: AppKind,
+}
+
+impl AppFilter {
+  fn matches(&self, app: &AppInfo) -> bool {
+    let kind_ok = match self.kind {
+      AppKind::All => true,
+      AppKind::System => app.is_system,
+      AppKind::ThirdParty => !app.is_system,
+    };
+    if !kind_ok {
+      return false;
+    }
+
+    if let Some(prefix) = self.prefix.as_deref().filter(|p| !p.is_empty()) {
+      if !app.package.starts_with(prefix) {
+        return false;
+      }
+    }
+
+    match self.keyword.as_deref().map(str::trim).filter(|k| !k.is_empty()) {
+      Some(keyword) => {
+        let keyword = keyword.to_lowercase();
+        app.package.to_lowercase().contains(&keyword)
+          || app.label.as_ref().is_some_and(|l| l.to_lowercase().contains(&keyword))
+      }
+      None => true,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct InstallOptions {
+  /// -r：覆盖安装，保留数据
+  #[serde(default)]
+  pub replace: bool,
+  /// -g：授予清单中声明的全部运行时权限
+  #[serde(default)]
+  pub grant_permissions: bool,
+  /// -d：允许版本降级
+  #[serde(default)]
+  pub allow_downgrade: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppOpMode {
+  Allow,
+  Ignore,
+  Deny,
+  Default,
+  Foreground,
+}
+
+impl AppOpMode {
+  fn as_arg(self) -> &'static str {
+    match self {
+      AppOpMode::Allow => "allow",
+      AppOpMode::Ignore => "ignore",
+      AppOpMode::Deny => "deny",
+      AppOpMode::Default => "default",
+      AppOpMode::Foreground => "foreground",
+    }
+  }
+}
+
+pub fn list_apps(
+  device_id: &str,
+  filter: &AppFilter,
+  user_id: Option<u32>,
+) -> Result<Vec<AppInfo>> {
+  let key = cache_key(device_id, user_id);
+  let now = Instant::now();
+
+  let cached = APP_CACHE.lock().ok().and_then(|cache| cache.get(&key).cloned());
+  let apps = match cached {
+    // 短时间内的重复请求（例如搜索框逐字输入）直接使用缓存
+    Some(entry) if now.duration_since(entry.checked_at) < APP_CACHE_TTL => entry.apps,
+    Some(entry)
+      if entry.fingerprint.is_some()
+        && package_fingerprint(device_id, user_id).ok() == entry.fingerprint =>
+    {
+      if let Ok(mut cache) = APP_CACHE.lock() {
+        if let Some(entry) = cache.get_mut(&key) {
+          entry.checked_at = now;
+        }
+      }
+      entry.apps
+    }
+    _ => reload_apps(device_id, user_id)?,
+  };
+
+  // 缓存的列表可能早于应用名的读取，过滤前补上
+  let labels = APP_LABELS.lock().ok();
+  Ok(
+    apps
+      .iter()
+      .map(|app| AppInfo {
+        label: labels
+          .as_ref()
+          .and_then(|labels| labels.get(&format!("{device_id}:{}", app.package)).cloned()),
+        ..app.clone()
+      })
+      .filter(|app| filter.matches(app))
+      .collect(),
+  )
+}
+
+/// 记录从 APK 读出的应用名，之后的应用列表会带上并可按其搜索
+pub(crate) fn remember_label(device_id: &str, package: &str, label: &str) {
+  if let Ok(mut labels) = APP_LABELS.lock() {
+    labels.insert(format!("{device_id}:{package}"), label.to_string());
+  }
+}
+
+// 未指定用户时 pm 作用于当前前台用户，不一定是用户 0，需要单独缓存
+fn cache_key(device_id: &str, user_id: Option<u32>) -> String {
+  match user_id {
+    Some(id) => format!("{device_id}:{id}"),
+    None => format!("{device_id}:current"),
+  }
+}
+
+/// 忽略缓存，重新拉取设备上的应用列表，返回应用数量
+pub fn refresh_apps(device_id: &str, user_id: Option<u32>) -> Result<usize> {
+  reload_apps(device_id, user_id).map(|apps| apps.len())
+}
+
+fn reload_apps(device_id: &str, user_id: Option<u32>) -> Result<Arc<Vec<AppInfo>>> {
+  let user = user_id.map(|id| id.to_string());
+  // 先取指纹再拉列表，期间有安装/卸载时下次检查会重新加载
+  let fingerprint = package_fingerprint(device_id, user_id).ok();
   let mut seen = HashSet::new();
   let mut apps = Vec::new();
 
-  for (args, is_system) in [
-    (["shell", "pm", "list", "packages", "-3"], false), // 第三方安装应用
-    (["shell", "pm", "list", "packages", "-s"], true),  // 系统应用
+  for (filter, is_system) in [
+    ("-3", false), // 第三方安装应用
+    ("-s", true),  // 系统应用
   ] {
+    let mut args = vec!["shell", "pm", "list", "packages", filter];
+    push_user(&mut args, &user);
     let raw = run_device(device_id, &args)?;
-    for pkg in raw.lines().filter_map(|line| line.strip_prefix("package:")) {
-      let pkg_lower = pkg.to_ascii_lowercase();
-      if let Some(k) = &keyword {
-        if !pkg_lower.contains(k) {
-          continue;
-        }
-      }
+    for pkg in raw.lines().filter_map(|line| line.trim().strip_prefix("package:")) {
       if !seen.insert(pkg.to_string()) {
         continue;
       }
@@ -30,7 +204,417 @@ pub fn list_apps(device_id: &str, keyword: Option<&str>) -> Result<Vec<AppInfo>>
     }
   }
 
+  let apps = Arc::new(apps);
+  if let Ok(mut cache) = APP_CACHE.lock() {
+    cache.insert(
+      cache_key(device_id, user_id),
+      AppCacheEntry {
+        apps: apps.clone(),
+        fingerprint,
+        checked_at: Instant::now(),
+      },
+    );
+  }
   Ok(apps)
 }
 
+/// 安装/卸载后清除该设备所有用户的缓存
+fn invalidate_app_cache(device_id: &str) {
+  if let Ok(mut cache) = APP_CACHE.lock() {
+    let prefix = format!("{device_id}:");
+    cache.retain(|key, _| !key.starts_with(&prefix));
+  }
+}
+
+/// 已安装包名集合的指纹，用于判断缓存是否过期
+///
+/// 在设备上计算 md5，输出远小于完整列表；数量不变的同时安装与卸载也能识别。
+/// Android 6.0 以下没有 md5sum，回退为拉取包名列表在本地计算。
+fn package_fingerprint(device_id: &str, user_id: Option<u32>) -> Result<String> {
+  let user = user_id.map(|id| id.to_string());
+  let mut args = vec!["shell", "pm", "list", "packages"];
+  push_user(&mut args, &user);
+  let list_len = args.len();
+  args.extend(["|", "sort", "|", "md5sum"]);
+  // 3f2c1a0b9e8d7c6b5a4f3e2d1c0b9a8f  -
+  let raw = run_device(device_id, &args)?;
+  let digest = raw.split_whitespace().next().unwrap_or_default();
+  // 空输入的 md5，说明 pm 没有输出，按失败处理
+  let empty = digest == "d41d8cd98f00b204e9800998ecf8427e";
+  if !empty && digest.len() == 32 && digest.chars().all(|c| c.is_ascii_hexdigit()) {
+    return Ok(digest.to_string());
+  }
+
+  args.truncate(list_len);
+  let raw = run_device(device_id, &args)?;
+  let mut packages: Vec<&str> = raw
+    .lines()
+    .filter_map(|line| line.trim().strip_prefix("package:"))
+    .collect();
+  if packages.is_empty() {
+    return Err(AdbError::ParseFailed(format!("包列表解析失败: {}", raw.trim())));
+  }
+  packages.sort_unstable();
+  let mut hasher = DefaultHasher::new();
+  packages.hash(&mut hasher);
+  Ok(format!("{:016x}", hasher.finish()))
+}
+
+/// 解析应用的启动 Activity，返回 `package/.Activity` 形式的组件名
+pub fn resolve_launcher_activity(
+  device_id: &str,
+  package: &str,
+  user_id: Option<u32>,
+) -> Result<String> {
+  let user = user_id.map(|id| id.to_string());
+  let mut args = vec![
+    "shell",
+    "cmd",
+    "package",
+    "resolve-activity",
+    "--brief",
+    "-a",
+    "android.intent.action.MAIN",
+    "-c",
+    "android.intent.category.LAUNCHER",
+  ];
+  push_user(&mut args, &user);
+  args.push(package);
+  let raw = run_device(device_id, &args)?;
+
+  // 输出格式通常为两行：priority=0 preferredOrder=0 ...，随后是组件名
+  raw
+    .lines()
+    .map(str::trim)
+    .rev()
+    .find(|line| line.starts_with(package) && line.contains('/'))
+    .map(|s| s.to_string())
+    .ok_or_else(|| AdbError::ParseFailed(format!("未找到 {package} 的启动 Activity")))
+}
+
+pub fn launch_app(device_id: &str, package: &str, user_id: Option<u32>) -> Result<()> {
+  let component = resolve_launcher_activity(device_id, package, user_id)?;
+  let user = user_id.map(|id| id.to_string());
+  let mut args = vec!["shell", "am", "start"];
+  push_user(&mut args, &user);
+  args.extend(["-n", &component]);
+  let raw = run_device(device_id, &args)?;
+  ensure_no_error(&raw)
+}
+
+pub fn force_stop_app(device_id: &str, package: &str, user_id: Option<u32>) -> Result<()> {
+  let user = user_id.map(|id| id.to_string());
+  let mut args = vec!["shell", "am", "force-stop"];
+  push_user(&mut args, &user);
+  args.push(package);
+  let raw = run_device(device_id, &args)?;
+  ensure_no_error(&raw)
+}
+
+pub fn clear_app_data(device_id: &str, package: &str, user_id: Option<u32>) -> Result<()> {
+  let user = user_id.map(|id| id.to_string());
+  let mut args = vec!["shell", "pm", "clear"];
+  push_user(&mut args, &user);
+  args.push(package);
+  let raw = run_device(device_id, &args)?;
+  if raw.trim() == "Success" {
+    Ok(())
+  } else {
+    Err(AdbError::CommandFailed(raw.trim().to_string()))
+  }
+}
+
+/// 仅杀死应用的后台进程（与 force-stop 不同，不会影响前台进程与已注册的闹钟等）
+pub fn kill_background(device_id: &str, package: &str, user_id: Option<u32>) -> Result<()> {
+  let user = user_id.map(|id| id.to_string());
+  let mut args = vec!["shell", "am", "kill"];
+  push_user(&mut args, &user);
+  args.push(package);
+  let raw = run_device(device_id, &args)?;
+  ensure_no_error(&raw)
+}
+
+/// 安装单个 APK；传入多个路径时视为同一应用的 split APK 集合，使用 install-multiple
+pub fn install_apk(
+  device_id: &str,
+  apk_paths: &[String],
+  options: &InstallOptions,
+  user_id: Option<u32>,
+) -> Result<()> {
+  if apk_paths.is_empty() {
+    return Err(AdbError::CommandFailed("未指定 APK 文件".into()));
+  }
+
+  let mut args = vec![if apk_paths.len() > 1 { "install-multiple" } else { "install" }];
+  if options.replace {
+    args.push("-r");
+  }
+  if options.grant_permissions {
+    args.push("-g");
+  }
+  if options.allow_downgrade {
+    args.push("-d");
+  }
+  // 只安装到指定用户，其余选项原样转给 pm install
+  let user = user_id.map(|id| id.to_string());
+  push_user(&mut args, &user);
+  args.extend(apk_paths.iter().map(String::as_str));
+
+  // 失败信息可能出现在 stdout（旧版本 adb）或 stderr（退出码非 0）中
+  let raw = match run_device(device_id, &args) {
+    Ok(raw) => raw,
+    Err(AdbError::CommandFailed(err)) => {
+      return Err(
+        InstallFailure::parse(&err)
+          .map(AdbError::InstallFailed)
+          .unwrap_or(AdbError::CommandFailed(err)),
+      );
+    }
+    Err(e) => return Err(e),
+  };
+
+  if let Some(failure) = InstallFailure::parse(&raw) {
+    return Err(AdbError::InstallFailed(failure));
+  }
+  if !raw.contains("Success") {
+    return Err(AdbError::CommandFailed(raw.trim().to_string()));
+  }
+  invalidate_app_cache(device_id);
+  Ok(())
+}
+
+pub fn uninstall_app(
+  device_id: &str,
+  package: &str,
+  keep_data: bool,
+  user_id: Option<u32>,
+) -> Result<()> {
+  // 指定用户时只从该用户卸载，需要直接调用 pm
+  let user = user_id.map(|id| id.to_string());
+  let mut args = match user {
+    Some(_) => vec!["shell", "pm", "uninstall"],
+    None => vec!["uninstall"],
+  };
+  if keep_data {
+    args.push("-k");
+  }
+  push_user(&mut args, &user);
+  args.push(package);
+
+  let raw = run_device(device_id, &args)?;
+  if raw.contains("Success") {
+    invalidate_app_cache(device_id);
+    Ok(())
+  } else {
+    // 例如 Failure [DELETE_FAILED_INTERNAL_ERROR]
+    Err(AdbError::CommandFailed(raw.trim().to_string()))
+  }
+}
+
+/// 获取当前处于前台（resumed）的应用
+pub fn current_foreground(device_id: &str) -> Result<ForegroundApp> {
+  let raw = run_device(device_id, &["shell", "dumpsys", "activity", "activities"])?;
+  // Android 9 及以下为 mResumedActivity，10+ 为 ResumedActivity / topResumedActivity
+  let resumed = raw.lines().map(str::trim).find_map(|line| {
+    ["mResumedActivity:", "ResumedActivity:", "topResumedActivity="]
+      .iter()
+      .find_map(|prefix| line.strip_prefix(prefix))
+      .and_then(parse_component)
+  });
+  if let Some(app) = resumed {
+    return Ok(app);
+  }
+
+  // 回退到窗口焦点：mCurrentFocus=Window{1a2b3c u0 com.example/com.example.MainActivity}
+  let raw = run_device(device_id, &["shell", "dumpsys", "window"])?;
+  raw
+    .lines()
+    .map(str::trim)
+    .find_map(|line| {
+      ["mCurrentFocus=", "mFocusedApp="]
+        .iter()
+        .find_map(|prefix| line.strip_prefix(prefix))
+        .and_then(parse_component)
+    })
+    .ok_or_else(|| AdbError::ParseFailed("未找到前台应用".into()))
+}
+
+/// 从 `ActivityRecord{... u0 com.example/.MainActivity t12}` 之类的记录中取出包名与 Activity
+fn parse_component(record: &str) -> Option<ForegroundApp> {
+  record
+    .split(|c: char| c.is_whitespace() || c == '{' || c == '}')
+    .find_map(|token| token.split_once('/'))
+    .filter(|(package, _)| package.contains('.'))
+    .map(|(package, activity)| ForegroundApp {
+      package: package.to_string(),
+      activity: Some(activity.to_string()).filter(|a| !a.is_empty()),
+    })
+}
+
+/// 列出应用申请的权限及授予状态
+pub fn list_permissions(
+  device_id: &str,
+  package: &str,
+  user_id: Option<u32>,
+) -> Result<Vec<PermissionInfo>> {
+  let raw = run_device(device_id, &["shell", "dumpsys", "package", package])?;
+  // 运行时权限按用户分别记录，未指定时取主用户
+  let target_user = user_id.unwrap_or(0);
+  let mut current_user: Option<u32> = None;
+  let mut requested: Vec<String> = Vec::new();
+  let mut granted: HashMap<String, (bool, bool)> = HashMap::new();
 
+  #[derive(PartialEq)]
+  enum Section {
+    Other,
+    Requested,
+    Install,
+    Runtime,
+  }
+  // 当前所在段落及其标题行的缩进，缩进回退到标题层级即段落结束
+  let mut section = (Section::Other, 0);
+
+  // 输出结构：
+  //   requested permissions:
+  //     android.permission.CAMERA
+  //   install permissions:
+  //     android.permission.INTERNET: granted=true
+  //   User 0: ceDataInode=...
+  //     runtime permissions:
+  //       android.permission.CAMERA: granted=false, flags=[ USER_SENSITIVE_WHEN_GRANTED ]
+  for line in raw.lines() {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+      continue;
+    }
+    let indent = line.len() - line.trim_start().len();
+    if let Some(rest) = trimmed.strip_prefix("User ") {
+      current_user = rest.split(':').next().and_then(|id| id.trim().parse().ok());
+    }
+
+    match trimmed {
+      "requested permissions:" => section = (Section::Requested, indent),
+      "install permissions:" => section = (Section::Install, indent),
+      "runtime permissions:" => section = (Section::Runtime, indent),
+      _ if indent <= section.1 => section = (Section::Other, indent),
+      _ => match section.0 {
+        Section::Requested => {
+          let name = trimmed.split(':').next().unwrap_or(trimmed).trim();
+          if !requested.iter().any(|p| p == name) {
+            requested.push(name.to_string());
+          }
+        }
+        Section::Runtime if current_user != Some(target_user) => {}
+        Section::Install | Section::Runtime => {
+          if let Some((name, rest)) = trimmed.split_once(':') {
+            let is_granted = rest.contains("granted=true");
+            let runtime = section.0 == Section::Runtime;
+            granted.insert(name.trim().to_string(), (is_granted, runtime));
+          }
+        }
+        Section::Other => {}
+      },
+    }
+  }
+
+  if requested.is_empty() && granted.is_empty() {
+    return Err(AdbError::ParseFailed(format!("未找到 {package} 的权限信息")));
+  }
+
+  // 部分系统不输出 requested permissions，以实际出现的权限为准补齐
+  for name in granted.keys() {
+    if !requested.contains(name) {
+      requested.push(name.clone());
+    }
+  }
+
+  Ok(
+    requested
+      .into_iter()
+      .map(|name| {
+        let (granted, runtime) = granted.get(&name).copied().unwrap_or((false, false));
+        PermissionInfo { name, granted, runtime }
+      })
+      .collect(),
+  )
+}
+
+pub fn set_permission(
+  device_id: &str,
+  package: &str,
+  permission: &str,
+  grant: bool,
+  user_id: Option<u32>,
+) -> Result<()> {
+  let user = user_id.map(|id| id.to_string());
+  let mut args = vec!["shell", "pm", if grant { "grant" } else { "revoke" }];
+  push_user(&mut args, &user);
+  args.extend([package, permission]);
+  let raw = run_device(device_id, &args)?;
+  ensure_no_error(&raw)
+}
+
+/// 列出应用当前的 app-ops 设置
+pub fn list_app_ops(
+  device_id: &str,
+  package: &str,
+  user_id: Option<u32>,
+) -> Result<Vec<AppOpInfo>> {
+  let user = user_id.map(|id| id.to_string());
+  let mut args = vec!["shell", "appops", "get"];
+  push_user(&mut args, &user);
+  args.push(package);
+  let raw = run_device(device_id, &args)?;
+  ensure_no_error(&raw)?;
+
+  // RUN_IN_BACKGROUND: allow; time=+1h2m ago
+  Ok(
+    raw
+      .lines()
+      .filter_map(|line| line.trim().split_once(':'))
+      .filter(|(op, _)| !op.is_empty() && op.chars().all(|c| c.is_ascii_uppercase() || c == '_'))
+      .map(|(op, rest)| AppOpInfo {
+        op: op.to_string(),
+        mode: rest.split(';').next().unwrap_or("").trim().to_string(),
+      })
+      .collect(),
+  )
+}
+
+pub fn set_app_op(
+  device_id: &str,
+  package: &str,
+  op: &str,
+  mode: AppOpMode,
+  user_id: Option<u32>,
+) -> Result<()> {
+  let user = user_id.map(|id| id.to_string());
+  let mut args = vec!["shell", "appops", "set"];
+  push_user(&mut args, &user);
+  args.extend([package, op, mode.as_arg()]);
+  let raw = run_device(device_id, &args)?;
+  ensure_no_error(&raw)
+}
+
+/// 追加 `--user <id>`；未指定用户时不追加，由系统使用当前用户
+pub(crate) fn push_user<'a>(args: &mut Vec<&'a str>, user: &'a Option<String>) {
+  if let Some(user) = user {
+    args.extend(["--user", user.as_str()]);
+  }
+}
+
+/// 部分 shell 命令失败时退出码仍为 0，只能从输出中识别错误
+fn ensure_no_error(raw: &str) -> Result<()> {
+  match raw
+    .lines()
+    .map(str::trim)
+    .find(|line| {
+      line.starts_with("Error")
+        || line.starts_with("Exception")
+        || line.starts_with("Security exception")
+    })
+  {
+    Some(line) => Err(AdbError::CommandFailed(line.to_string())),
+    None => Ok(()),
+  }
+}
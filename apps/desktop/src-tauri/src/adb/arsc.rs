@@ -0,0 +1,695 @@
+//! APK 二进制资源的最小解析，只覆盖定位启动图标与应用名所需的部分：
+//! 二进制 XML（AndroidManifest.xml、自适应图标）与资源表 resources.arsc。
+
+const RES_STRING_POOL_TYPE: u16 = 0x0001;
+const RES_TABLE_TYPE: u16 = 0x0002;
+const RES_XML_TYPE: u16 = 0x0003;
+const RES_XML_START_ELEMENT_TYPE: u16 = 0x0102;
+const RES_XML_RESOURCE_MAP_TYPE: u16 = 0x0180;
+const RES_TABLE_PACKAGE_TYPE: u16 = 0x0200;
+const RES_TABLE_TYPE_TYPE: u16 = 0x0201;
+
+// ResStringPool_header.flags
+const UTF8_FLAG: u32 = 0x0100;
+
+// ResTable_type.flags
+const FLAG_SPARSE: u8 = 0x01;
+const FLAG_OFFSET16: u8 = 0x02;
+const NO_ENTRY: u32 = 0xFFFF_FFFF;
+
+// ResTable_entry.flags
+const FLAG_COMPLEX: u16 = 0x0001;
+const FLAG_COMPACT: u16 = 0x0008;
+
+// Res_value.dataType
+const TYPE_REFERENCE: u8 = 0x01;
+const TYPE_STRING: u8 = 0x03;
+const TYPE_DYNAMIC_REFERENCE: u8 = 0x07;
+
+// android.R.attr
+const ATTR_LABEL: u32 = 0x0101_0001;
+const ATTR_ICON: u32 = 0x0101_0002;
+const ATTR_SRC: u32 = 0x0101_0119;
+const ATTR_DRAWABLE: u32 = 0x0101_0199;
+
+// 资源引用链（别名、自适应图标前景层）最多跟随的层数
+const MAX_REFERENCE_DEPTH: usize = 4;
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+  let bytes = data.get(offset..offset.checked_add(2)?)?;
+  Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+  let bytes = data.get(offset..offset.checked_add(4)?)?;
+  Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// ResChunk_header：所有二进制资源都由嵌套的 chunk 组成
+#[derive(Debug, Clone, Copy)]
+struct Chunk {
+  kind: u16,
+  start: usize,
+  header_size: usize,
+  size: usize,
+}
+
+impl Chunk {
+  fn at(data: &[u8], start: usize) -> Option<Chunk> {
+    let chunk = Chunk {
+      kind: read_u16(data, start)?,
+      start,
+      header_size: read_u16(data, start + 2)? as usize,
+      size: read_u32(data, start + 4)? as usize,
+    };
+    let valid = chunk.header_size >= 8
+      && chunk.size >= chunk.header_size
+      && start.checked_add(chunk.size).is_some_and(|end| end <= data.len());
+    valid.then_some(chunk)
+  }
+
+  fn end(&self) -> usize {
+    self.start + self.size
+  }
+
+  fn body(&self) -> usize {
+    self.start + self.header_size
+  }
+
+  /// 依次返回头部之后的子 chunk，遇到损坏的 chunk 时停止
+  fn children(&self, data: &[u8]) -> Vec<Chunk> {
+    let mut result = Vec::new();
+    let mut offset = self.body();
+    while offset < self.end() {
+      let Some(child) = Chunk::at(data, offset).filter(|c| c.end() <= self.end()) else {
+        break;
+      };
+      result.push(child);
+      offset = child.end();
+    }
+    result
+  }
+}
+
+struct StringPool<'a> {
+  data: &'a [u8],
+  offsets: Vec<usize>,
+  utf8: bool,
+}
+
+impl<'a> StringPool<'a> {
+  fn parse(data: &'a [u8], chunk: Chunk) -> Option<StringPool<'a>> {
+    let count = read_u32(data, chunk.start + 8)? as usize;
+    let flags = read_u32(data, chunk.start + 16)?;
+    let strings_start = chunk.start + read_u32(data, chunk.start + 20)? as usize;
+    let offsets = (0..count)
+      .map(|i| read_u32(data, chunk.body() + i * 4).map(|o| strings_start + o as usize))
+      .collect::<Option<Vec<_>>>()?;
+    Some(StringPool {
+      data,
+      offsets,
+      utf8: flags & UTF8_FLAG != 0,
+    })
+  }
+
+  fn get(&self, index: u32) -> Option<String> {
+    let offset = *self.offsets.get(index as usize)?;
+    if self.utf8 {
+      // 先是 UTF-16 长度，再是 UTF-8 字节数，各占 1~2 字节
+      let (_, offset) = self.utf8_length(offset)?;
+      let (len, offset) = self.utf8_length(offset)?;
+      let bytes = self.data.get(offset..offset + len)?;
+      Some(String::from_utf8_lossy(bytes).into_owned())
+    } else {
+      let first = read_u16(self.data, offset)? as usize;
+      let (len, offset) = if first & 0x8000 != 0 {
+        let second = read_u16(self.data, offset + 2)? as usize;
+        (((first & 0x7FFF) << 16) | second, offset + 4)
+      } else {
+        (first, offset + 2)
+      };
+      let units = (0..len)
+        .map(|i| read_u16(self.data, offset + i * 2))
+        .collect::<Option<Vec<_>>>()?;
+      Some(String::from_utf16_lossy(&units))
+    }
+  }
+
+  fn utf8_length(&self, offset: usize) -> Option<(usize, usize)> {
+    let first = *self.data.get(offset)? as usize;
+    if first & 0x80 != 0 {
+      let second = *self.data.get(offset + 1)? as usize;
+      Some((((first & 0x7F) << 8) | second, offset + 2))
+    } else {
+      Some((first, offset + 1))
+    }
+  }
+}
+
+/// 二进制 XML 开始标签上的一个属性
+struct XmlAttr {
+  name: Option<String>,
+  res_id: Option<u32>,
+  data_type: u8,
+  data: u32,
+  /// 取值为字符串字面量时的内容
+  string: Option<String>,
+}
+
+impl XmlAttr {
+  /// 混淆后的 APK 可能去掉属性名，因此优先按资源 id 匹配
+  fn is(&self, res_id: u32, name: &str) -> bool {
+    match self.res_id {
+      Some(id) => id == res_id,
+      None => self.name.as_deref() == Some(name),
+    }
+  }
+
+  fn reference(&self) -> Option<u32> {
+    matches!(self.data_type, TYPE_REFERENCE | TYPE_DYNAMIC_REFERENCE).then_some(self.data)
+  }
+}
+
+/// 依次返回二进制 XML 中的开始标签：(元素名, 属性)
+fn xml_elements(data: &[u8]) -> Option<Vec<(String, Vec<XmlAttr>)>> {
+  let root = Chunk::at(data, 0).filter(|c| c.kind == RES_XML_TYPE)?;
+  let mut strings = None;
+  let mut resource_map: Vec<u32> = Vec::new();
+  let mut elements = Vec::new();
+
+  for chunk in root.children(data) {
+    match chunk.kind {
+      RES_STRING_POOL_TYPE => strings = StringPool::parse(data, chunk),
+      RES_XML_RESOURCE_MAP_TYPE => {
+        resource_map = (chunk.body()..chunk.end())
+          .step_by(4)
+          .filter_map(|offset| read_u32(data, offset))
+          .collect();
+      }
+      RES_XML_START_ELEMENT_TYPE => {
+        let Some(ref strings) = strings else {
+          continue;
+        };
+        // ResXMLTree_attrExt
+        let ext = chunk.body();
+        let Some(name) = read_u32(data, ext + 4).and_then(|i| strings.get(i)) else {
+          continue;
+        };
+        let attr_start = ext + read_u16(data, ext + 8).unwrap_or(20) as usize;
+        let attr_size = read_u16(data, ext + 10).unwrap_or(20) as usize;
+        let attr_count = read_u16(data, ext + 12).unwrap_or(0) as usize;
+        let attrs = (0..attr_count)
+          .filter_map(|i| {
+            let offset = attr_start + i * attr_size;
+            let name_index = read_u32(data, offset + 4)?;
+            let data_type = *data.get(offset + 15)?;
+            let value = read_u32(data, offset + 16)?;
+            Some(XmlAttr {
+              name: strings.get(name_index),
+              res_id: resource_map.get(name_index as usize).copied(),
+              data_type,
+              data: value,
+              string: (data_type == TYPE_STRING).then(|| strings.get(value)).flatten(),
+            })
+          })
+          .collect();
+        elements.push((name, attrs));
+      }
+      _ => {}
+    }
+  }
+
+  Some(elements)
+}
+
+/// 读取 AndroidManifest.xml 中 `<application android:icon>` 引用的资源 id
+pub(crate) fn manifest_icon(manifest: &[u8]) -> Option<u32> {
+  xml_elements(manifest)?
+    .into_iter()
+    .find(|(name, _)| name == "application")?
+    .1
+    .iter()
+    .find(|attr| attr.is(ATTR_ICON, "icon"))?
+    .reference()
+}
+
+/// 读取 `<application android:label>`：字面量直接返回，资源引用经 resources.arsc 解析
+pub(crate) fn manifest_label(manifest: &[u8], table: Option<&ResourceTable>) -> Option<String> {
+  let elements = xml_elements(manifest)?;
+  let (_, attrs) = elements.iter().find(|(name, _)| name == "application")?;
+  let attr = attrs.iter().find(|attr| attr.is(ATTR_LABEL, "label"))?;
+  let label = match attr.string.clone() {
+    Some(label) => label,
+    None => table?.resolve_string(attr.reference()?)?,
+  };
+  let label = label.trim();
+  (!label.is_empty()).then(|| label.to_string())
+}
+
+/// 图标为 XML drawable 时，取其中引用的位图资源 id：
+/// 自适应图标的 `<foreground android:drawable>`，或 `<bitmap android:src>` 等
+pub(crate) fn drawable_reference(xml: &[u8]) -> Option<u32> {
+  xml_elements(xml)?.into_iter().find_map(|(name, attrs)| {
+    let (res_id, attr_name) = match name.as_str() {
+      "foreground" | "inset" | "item" => (ATTR_DRAWABLE, "drawable"),
+      "bitmap" | "nine-patch" => (ATTR_SRC, "src"),
+      _ => return None,
+    };
+    attrs.iter().find(|attr| attr.is(res_id, attr_name))?.reference()
+  })
+}
+
+/// resources.arsc 中文件类资源在某个配置下的取值
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ResourceFile {
+  /// ResTable_config.density，0 为默认，0xFFFE 为 anydpi，0xFFFF 为 nodpi
+  pub density: u16,
+  /// APK 内的路径，混淆后可能形如 `res/a1.png`、`r/x/b.xml`
+  pub path: String,
+}
+
+struct Package {
+  id: u32,
+  types: Vec<Chunk>,
+}
+
+pub(crate) struct ResourceTable<'a> {
+  data: &'a [u8],
+  strings: StringPool<'a>,
+  packages: Vec<Package>,
+}
+
+impl<'a> ResourceTable<'a> {
+  pub(crate) fn parse(data: &'a [u8]) -> Option<ResourceTable<'a>> {
+    let root = Chunk::at(data, 0).filter(|c| c.kind == RES_TABLE_TYPE)?;
+    let mut strings = None;
+    let mut packages = Vec::new();
+
+    for chunk in root.children(data) {
+      match chunk.kind {
+        RES_STRING_POOL_TYPE if strings.is_none() => strings = StringPool::parse(data, chunk),
+        RES_TABLE_PACKAGE_TYPE => packages.push(Package {
+          id: read_u32(data, chunk.start + 8)?,
+          types: chunk
+            .children(data)
+            .into_iter()
+            .filter(|c| c.kind == RES_TABLE_TYPE_TYPE)
+            .collect(),
+        }),
+        _ => {}
+      }
+    }
+
+    Some(ResourceTable {
+      data,
+      strings: strings?,
+      packages,
+    })
+  }
+
+  /// 返回资源在各配置下对应的文件，资源别名（`@mipmap/a` 指向 `@mipmap/b`）会继续跟随
+  pub(crate) fn resolve_files(&self, id: u32) -> Vec<ResourceFile> {
+    let mut result = Vec::new();
+    self.collect_files(id, 0, &mut result);
+    result
+  }
+
+  fn collect_files(&self, id: u32, depth: usize, result: &mut Vec<ResourceFile>) {
+    if depth > MAX_REFERENCE_DEPTH {
+      return;
+    }
+    for (chunk, data_type, data) in self.values(id) {
+      match data_type {
+        TYPE_STRING => {
+          if let Some(path) = self.strings.get(data) {
+            result.push(ResourceFile {
+              density: self.density(chunk),
+              path,
+            });
+          }
+        }
+        TYPE_REFERENCE | TYPE_DYNAMIC_REFERENCE => self.collect_files(data, depth + 1, result),
+        _ => {}
+      }
+    }
+  }
+
+  /// 返回字符串资源的取值，优先中文配置，其次默认配置
+  pub(crate) fn resolve_string(&self, id: u32) -> Option<String> {
+    self.resolve_string_at(id, 0)
+  }
+
+  fn resolve_string_at(&self, id: u32, depth: usize) -> Option<String> {
+    if depth > MAX_REFERENCE_DEPTH {
+      return None;
+    }
+    let mut values = self.values(id);
+    values.sort_by_key(|(chunk, _, _)| match self.language(*chunk) {
+      [b'z', b'h'] => 0,
+      [0, 0] => 1,
+      _ => 2,
+    });
+    values.into_iter().find_map(|(_, data_type, data)| match data_type {
+      TYPE_STRING => self.strings.get(data),
+      TYPE_REFERENCE | TYPE_DYNAMIC_REFERENCE => self.resolve_string_at(data, depth + 1),
+      _ => None,
+    })
+  }
+
+  /// 资源 id 为 0xPPTTEEEE：包 id、类型 id、条目下标；返回 (所在类型 chunk, dataType, data)
+  fn values(&self, id: u32) -> Vec<(Chunk, u8, u32)> {
+    let package_id = id >> 24;
+    let type_id = ((id >> 16) & 0xFF) as u8;
+    let index = id & 0xFFFF;
+    // 只有一个包时不校验包 id，兼容共享库（包 id 为 0）
+    let Some(package) = self
+      .packages
+      .iter()
+      .find(|p| p.id == package_id)
+      .or_else(|| self.packages.first().filter(|_| self.packages.len() == 1))
+    else {
+      return Vec::new();
+    };
+
+    package
+      .types
+      .iter()
+      .filter(|chunk| self.data.get(chunk.start + 8) == Some(&type_id))
+      .filter_map(|chunk| {
+        let (data_type, data) = self.entry_value(*chunk, index)?;
+        Some((*chunk, data_type, data))
+      })
+      .collect()
+  }
+
+  fn density(&self, chunk: Chunk) -> u16 {
+    // ResTable_config 紧跟在 ResTable_type 的前 20 字节之后，density 位于其第 14 字节
+    let config = chunk.start + 20;
+    match read_u32(self.data, config) {
+      Some(size) if size >= 16 => read_u16(self.data, config + 14).unwrap_or(0),
+      _ => 0,
+    }
+  }
+
+  fn language(&self, chunk: Chunk) -> [u8; 2] {
+    // ResTable_config 的第 8、9 字节为语言代码，0 表示不限语言
+    let config = chunk.start + 20;
+    match read_u32(self.data, config) {
+      Some(size) if size >= 12 => [
+        self.data.get(config + 8).copied().unwrap_or(0),
+        self.data.get(config + 9).copied().unwrap_or(0),
+      ],
+      _ => [0, 0],
+    }
+  }
+
+  fn entry_value(&self, chunk: Chunk, index: u32) -> Option<(u8, u32)> {
+    let data = self.data;
+    let flags = *data.get(chunk.start + 9)?;
+    let count = read_u32(data, chunk.start + 12)?;
+    let entries_start = chunk.start + read_u32(data, chunk.start + 16)? as usize;
+    let offsets = chunk.body();
+
+    let offset = if flags & FLAG_SPARSE != 0 {
+      // 稀疏存储：每项为 (条目下标, 偏移 / 4)
+      (0..count as usize).find_map(|i| {
+        let entry_index = read_u16(data, offsets + i * 4)?;
+        (u32::from(entry_index) == index)
+          .then(|| read_u16(data, offsets + i * 4 + 2).map(|o| o as usize * 4))
+          .flatten()
+      })?
+    } else if index >= count {
+      return None;
+    } else if flags & FLAG_OFFSET16 != 0 {
+      let offset = read_u16(data, offsets + index as usize * 2)?;
+      if offset == 0xFFFF {
+        return None;
+      }
+      offset as usize * 4
+    } else {
+      let offset = read_u32(data, offsets + index as usize * 4)?;
+      if offset == NO_ENTRY {
+        return None;
+      }
+      offset as usize
+    };
+
+    // ResTable_entry：size(u16) flags(u16) key(u32)，其后为 Res_value
+    let entry = entries_start + offset;
+    let entry_flags = read_u16(data, entry + 2)?;
+    if entry_flags & FLAG_COMPACT != 0 {
+      return Some(((entry_flags >> 8) as u8, read_u32(data, entry + 4)?));
+    }
+    if entry_flags & FLAG_COMPLEX != 0 {
+      return None;
+    }
+    let value = entry + read_u16(data, entry)? as usize;
+    Some((*data.get(value + 3)?, read_u32(data, value + 4)?))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn chunk(kind: u16, header: &[u8], body: &[u8]) -> Vec<u8> {
+    let header_size = 8 + header.len();
+    let mut out = Vec::new();
+    out.extend(kind.to_le_bytes());
+    out.extend((header_size as u16).to_le_bytes());
+    out.extend(((header_size + body.len()) as u32).to_le_bytes());
+    out.extend(header);
+    out.extend(body);
+    out
+  }
+
+  fn string_pool(strings: &[&str], utf8: bool) -> Vec<u8> {
+    let mut offsets = Vec::new();
+    let mut data = Vec::new();
+    for s in strings {
+      offsets.extend((data.len() as u32).to_le_bytes());
+      if utf8 {
+        data.push(s.chars().count() as u8);
+        data.push(s.len() as u8);
+        data.extend(s.as_bytes());
+        data.push(0);
+      } else {
+        let units: Vec<u16> = s.encode_utf16().collect();
+        data.extend((units.len() as u16).to_le_bytes());
+        units.iter().for_each(|u| data.extend(u.to_le_bytes()));
+        data.extend([0, 0]);
+      }
+    }
+    while data.len() % 4 != 0 {
+      data.push(0);
+    }
+    let mut header = Vec::new();
+    header.extend((strings.len() as u32).to_le_bytes());
+    header.extend(0u32.to_le_bytes());
+    header.extend((if utf8 { UTF8_FLAG } else { 0 }).to_le_bytes());
+    header.extend((28 + offsets.len() as u32).to_le_bytes());
+    header.extend(0u32.to_le_bytes());
+    offsets.extend(data);
+    chunk(RES_STRING_POOL_TYPE, &header, &offsets)
+  }
+
+  // (元素名, [(属性名, 属性资源 id, 引用的资源 id)])
+  type Element<'a> = (&'a str, &'a [(&'a str, u32, u32)]);
+
+  /// 生成只含开始标签的二进制 XML
+  fn xml(elements: &[Element], utf8: bool) -> Vec<u8> {
+    // 有资源 id 的属性名必须排在字符串池最前面，与资源映射一一对应
+    let mut strings: Vec<&str> = Vec::new();
+    let mut resource_map = Vec::new();
+    for (_, attrs) in elements {
+      for (name, res_id, _) in attrs.iter() {
+        if !strings.contains(name) {
+          strings.push(name);
+          resource_map.extend(res_id.to_le_bytes());
+        }
+      }
+    }
+    for (name, _) in elements {
+      strings.push(name);
+    }
+    let index = |s: &str| strings.iter().position(|v| *v == s).unwrap() as u32;
+
+    let mut body = string_pool(&strings, utf8);
+    body.extend(chunk(RES_XML_RESOURCE_MAP_TYPE, &[], &resource_map));
+    for (name, attrs) in elements {
+      let mut ext = Vec::new();
+      ext.extend(NO_ENTRY.to_le_bytes());
+      ext.extend(index(name).to_le_bytes());
+      ext.extend(20u16.to_le_bytes());
+      ext.extend(20u16.to_le_bytes());
+      ext.extend((attrs.len() as u16).to_le_bytes());
+      ext.extend([0u8; 6]);
+      for (attr, _, target) in attrs.iter() {
+        ext.extend(NO_ENTRY.to_le_bytes());
+        ext.extend(index(attr).to_le_bytes());
+        ext.extend(NO_ENTRY.to_le_bytes());
+        ext.extend([8, 0, 0, TYPE_REFERENCE]);
+        ext.extend(target.to_le_bytes());
+      }
+      let mut header = Vec::new();
+      header.extend(1u32.to_le_bytes());
+      header.extend(NO_ENTRY.to_le_bytes());
+      body.extend(chunk(RES_XML_START_ELEMENT_TYPE, &header, &ext));
+    }
+    chunk(RES_XML_TYPE, &[], &body)
+  }
+
+  /// 生成资源类型 chunk，values 为各条目的 (dataType, data)，None 表示该配置下没有此条目
+  fn type_chunk(type_id: u8, density: u16, values: &[Option<(u8, u32)>]) -> Vec<u8> {
+    localized_type_chunk(type_id, [0, 0], density, values)
+  }
+
+  fn localized_type_chunk(
+    type_id: u8,
+    language: [u8; 2],
+    density: u16,
+    values: &[Option<(u8, u32)>],
+  ) -> Vec<u8> {
+    let mut config = vec![0u8; 64];
+    config[..4].copy_from_slice(&64u32.to_le_bytes());
+    config[8..10].copy_from_slice(&language);
+    config[14..16].copy_from_slice(&density.to_le_bytes());
+
+    let mut offsets = Vec::new();
+    let mut entries = Vec::new();
+    for value in values {
+      match value {
+        Some((data_type, data)) => {
+          offsets.extend((entries.len() as u32).to_le_bytes());
+          entries.extend(8u16.to_le_bytes());
+          entries.extend(0u16.to_le_bytes());
+          entries.extend(0u32.to_le_bytes());
+          entries.extend([8, 0, 0, *data_type]);
+          entries.extend(data.to_le_bytes());
+        }
+        None => offsets.extend(NO_ENTRY.to_le_bytes()),
+      }
+    }
+
+    let mut header = vec![type_id, 0, 0, 0];
+    header.extend((values.len() as u32).to_le_bytes());
+    let entries_start = 8 + 12 + config.len() + offsets.len();
+    header.extend((entries_start as u32).to_le_bytes());
+    header.extend(config);
+    offsets.extend(entries);
+    chunk(RES_TABLE_TYPE_TYPE, &header, &offsets)
+  }
+
+  fn table(paths: &[&str], types: &[Vec<u8>]) -> Vec<u8> {
+    let mut package_header = Vec::new();
+    package_header.extend(0x7Fu32.to_le_bytes());
+    package_header.extend([0u8; 256]);
+    package_header.extend([0u8; 20]);
+    let package = chunk(RES_TABLE_PACKAGE_TYPE, &package_header, &types.concat());
+
+    let mut body = string_pool(paths, true);
+    body.extend(package);
+    chunk(RES_TABLE_TYPE, &1u32.to_le_bytes(), &body)
+  }
+
+  const ICON: u32 = 0x7F03_0001;
+  const FOREGROUND: u32 = 0x7F03_0002;
+  const APP_NAME: u32 = 0x7F0B_0000;
+
+  #[test]
+  fn manifest_icon_from_application() {
+    let manifest = xml(
+      &[
+        ("manifest", &[("versionCode", 0x0101_021B, 1)]),
+        ("application", &[("theme", 0x0101_0000, 0x7F0F_0001), ("icon", ATTR_ICON, ICON)]),
+      ],
+      false,
+    );
+    assert_eq!(manifest_icon(&manifest), Some(ICON));
+  }
+
+  #[test]
+  fn manifest_icon_without_attribute_names() {
+    // 混淆工具把属性名替换成空串时仍可通过资源映射识别
+    let manifest = xml(&[("application", &[("", ATTR_ICON, ICON)])], true);
+    assert_eq!(manifest_icon(&manifest), Some(ICON));
+    assert_eq!(manifest_icon(b"not a binary xml"), None);
+  }
+
+  #[test]
+  fn adaptive_icon_foreground() {
+    let adaptive = xml(
+      &[
+        ("adaptive-icon", &[]),
+        ("background", &[("drawable", ATTR_DRAWABLE, 0x7F05_0001)]),
+        ("foreground", &[("drawable", ATTR_DRAWABLE, FOREGROUND)]),
+      ],
+      false,
+    );
+    assert_eq!(drawable_reference(&adaptive), Some(FOREGROUND));
+  }
+
+  #[test]
+  fn resolve_obfuscated_paths_per_density() {
+    let paths = ["r/a/a.xml", "res/a1.png", "res/b2.webp", "res/c3.png"];
+    let arsc = table(
+      &paths,
+      &[
+        type_chunk(3, 0xFFFE, &[None, Some((TYPE_STRING, 0))]),
+        type_chunk(3, 320, &[None, Some((TYPE_STRING, 1)), Some((TYPE_STRING, 3))]),
+        // 别名：xxxhdpi 下图标引用前景资源
+        type_chunk(3, 640, &[None, Some((TYPE_REFERENCE, FOREGROUND)), None]),
+        type_chunk(3, 480, &[None, None, Some((TYPE_STRING, 2))]),
+      ],
+    );
+    let table = ResourceTable::parse(&arsc).unwrap();
+    let file = |density: u16, path: &str| ResourceFile {
+      density,
+      path: path.to_string(),
+    };
+
+    assert_eq!(
+      table.resolve_files(ICON),
+      vec![
+        file(0xFFFE, "r/a/a.xml"),
+        file(320, "res/a1.png"),
+        file(320, "res/c3.png"),
+        file(480, "res/b2.webp"),
+      ]
+    );
+    assert_eq!(
+      table.resolve_files(FOREGROUND),
+      vec![file(320, "res/c3.png"), file(480, "res/b2.webp")]
+    );
+    assert!(table.resolve_files(0x7F03_0009).is_empty());
+    assert!(table.resolve_files(0x0108_0093).is_empty());
+  }
+
+  #[test]
+  fn manifest_label_prefers_chinese() {
+    let manifest = xml(&[("application", &[("label", ATTR_LABEL, APP_NAME)])], true);
+    let strings = ["Example", "示例", "Exemple"];
+    let arsc = table(
+      &strings,
+      &[
+        localized_type_chunk(0x0B, *b"fr", 0, &[Some((TYPE_STRING, 2))]),
+        type_chunk(0x0B, 0, &[Some((TYPE_STRING, 0))]),
+        localized_type_chunk(0x0B, *b"zh", 0, &[Some((TYPE_STRING, 1))]),
+      ],
+    );
+    let resources = ResourceTable::parse(&arsc).unwrap();
+    assert_eq!(manifest_label(&manifest, Some(&resources)).as_deref(), Some("示例"));
+    assert_eq!(manifest_label(&manifest, None), None);
+
+    // 没有中文翻译时使用默认配置
+    let arsc = table(
+      &strings,
+      &[
+        localized_type_chunk(0x0B, *b"fr", 0, &[Some((TYPE_STRING, 2))]),
+        type_chunk(0x0B, 0, &[Some((TYPE_STRING, 0))]),
+      ],
+    );
+    let resources = ResourceTable::parse(&arsc).unwrap();
+    assert_eq!(manifest_label(&manifest, Some(&resources)).as_deref(), Some("Example"));
+  }
+}
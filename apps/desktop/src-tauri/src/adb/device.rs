@@ -1,7 +1,7 @@
 use crate::adb::{
-  command::run_host,
+  command::{run_device, run_host},
   error::Result,
-  DeviceInfo,
+  DeviceInfo, UserInfo,
 };
 
 pub fn list_devices() -> Result<Vec<DeviceInfo>> {
@@ -39,3 +39,42 @@ fn list_with_cli() -> Result<Vec<DeviceInfo>> {
   Ok(devices)
 }
 
+/// 解析 `pm list users`，包含主用户、访客以及工作资料
+pub fn list_users(device_id: &str) -> Result<Vec<UserInfo>> {
+  let raw = run_device(device_id, &["shell", "pm", "list", "users"])?;
+  let mut users = Vec::new();
+
+  // Users:
+  // 	UserInfo{0:Owner:c13} running
+  // 	UserInfo{10:Work profile:1030} running
+  for line in raw.lines() {
+    let line = line.trim();
+    let Some(rest) = line.strip_prefix("UserInfo{") else {
+      continue;
+    };
+    let Some((info, tail)) = rest.split_once('}') else {
+      continue;
+    };
+
+    // 名称中可能包含冒号，id 取第一段、flags 取最后一段
+    let mut parts = info.split(':');
+    let id = match parts.next().and_then(|id| id.parse::<u32>().ok()) {
+      Some(id) => id,
+      None => continue,
+    };
+    let mut rest: Vec<&str> = parts.collect();
+    let flags = rest
+      .pop()
+      .and_then(|f| u32::from_str_radix(f, 16).ok())
+      .unwrap_or(0);
+
+    users.push(UserInfo {
+      id,
+      name: rest.join(":"),
+      flags,
+      running: tail.contains("running"),
+    });
+  }
+
+  Ok(users)
+}
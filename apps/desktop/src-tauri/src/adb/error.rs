@@ -1,3 +1,5 @@
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::fmt;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -8,10 +10,105 @@ pub enum AdbError {
   CommandFailed(String),
   #[error("ADB 输出解析失败: {0}")]
   ParseFailed(String),
+  #[error("安装失败: {0}")]
+  InstallFailed(InstallFailure),
+  #[error("文件读写失败: {0}")]
+  Io(#[from] std::io::Error),
   #[error("ADB 客户端错误: {0}")]
   #[allow(dead_code)]
   Client(String),
 }
 
-pub type Result<T> = std::result::Result<T, AdbError>;
+/// `pm install` 返回的 `INSTALL_FAILED_*` / `INSTALL_PARSE_FAILED_*` 失败原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstallFailure {
+  VersionDowngrade,
+  AlreadyExists,
+  UpdateIncompatible,
+  InsufficientStorage,
+  NoMatchingAbis,
+  OlderSdk,
+  TestOnly,
+  InvalidApk(String), // INSTALL_FAILED_INVALID_APK 或 INSTALL_PARSE_FAILED_*
+  MissingSplit,
+  UserRestricted,
+  Aborted,
+  Other(String),
+}
+
+impl InstallFailure {
+  /// 从输出中提取失败码，例如 `Failure [INSTALL_FAILED_VERSION_DOWNGRADE: ...]`
+  pub fn parse(raw: &str) -> Option<Self> {
+    let start = raw.find("INSTALL_")?;
+    let code: String = raw[start..]
+      .chars()
+      .take_while(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || *c == '_')
+      .collect();
+
+    Some(match code.as_str() {
+      "INSTALL_FAILED_VERSION_DOWNGRADE" => Self::VersionDowngrade,
+      "INSTALL_FAILED_ALREADY_EXISTS" => Self::AlreadyExists,
+      "INSTALL_FAILED_UPDATE_INCOMPATIBLE" => Self::UpdateIncompatible,
+      "INSTALL_FAILED_INSUFFICIENT_STORAGE" => Self::InsufficientStorage,
+      "INSTALL_FAILED_NO_MATCHING_ABIS" => Self::NoMatchingAbis,
+      "INSTALL_FAILED_OLDER_SDK" => Self::OlderSdk,
+      "INSTALL_FAILED_TEST_ONLY" => Self::TestOnly,
+      "INSTALL_FAILED_INVALID_APK" => Self::InvalidApk(code),
+      "INSTALL_FAILED_MISSING_SPLIT" => Self::MissingSplit,
+      "INSTALL_FAILED_USER_RESTRICTED" => Self::UserRestricted,
+      "INSTALL_FAILED_ABORTED" => Self::Aborted,
+      _ if code.starts_with("INSTALL_PARSE_FAILED") => Self::InvalidApk(code),
+      _ => Self::Other(code),
+    })
+  }
+
+  /// 原始失败码，例如 `INSTALL_FAILED_VERSION_DOWNGRADE`
+  pub fn code(&self) -> &str {
+    match self {
+      Self::VersionDowngrade => "INSTALL_FAILED_VERSION_DOWNGRADE",
+      Self::AlreadyExists => "INSTALL_FAILED_ALREADY_EXISTS",
+      Self::UpdateIncompatible => "INSTALL_FAILED_UPDATE_INCOMPATIBLE",
+      Self::InsufficientStorage => "INSTALL_FAILED_INSUFFICIENT_STORAGE",
+      Self::NoMatchingAbis => "INSTALL_FAILED_NO_MATCHING_ABIS",
+      Self::OlderSdk => "INSTALL_FAILED_OLDER_SDK",
+      Self::TestOnly => "INSTALL_FAILED_TEST_ONLY",
+      Self::MissingSplit => "INSTALL_FAILED_MISSING_SPLIT",
+      Self::UserRestricted => "INSTALL_FAILED_USER_RESTRICTED",
+      Self::Aborted => "INSTALL_FAILED_ABORTED",
+      Self::InvalidApk(code) | Self::Other(code) => code,
+    }
+  }
+}
 
+/// 序列化为 `{ code, message }`，前端可按失败码给出对应的处理建议
+impl Serialize for InstallFailure {
+  fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    let mut state = serializer.serialize_struct("InstallFailure", 2)?;
+    state.serialize_field("code", self.code())?;
+    state.serialize_field("message", &self.to_string())?;
+    state.end()
+  }
+}
+
+impl fmt::Display for InstallFailure {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let message = match self {
+      Self::VersionDowngrade => "设备上已安装更高版本，可开启允许降级后重试",
+      Self::AlreadyExists => "应用已存在，可开启覆盖安装后重试",
+      Self::UpdateIncompatible => "签名与已安装版本不一致，请先卸载",
+      Self::InsufficientStorage => "设备存储空间不足",
+      Self::NoMatchingAbis => "APK 不包含设备支持的 ABI",
+      Self::OlderSdk => "设备系统版本低于应用要求的 minSdkVersion",
+      Self::TestOnly => "testOnly 应用不允许直接安装",
+      Self::InvalidApk(_) => "APK 文件无效或已损坏",
+      Self::MissingSplit => "缺少必需的 split APK",
+      Self::UserRestricted => "用户在设备上拒绝了安装",
+      Self::Aborted => "安装被中止",
+      Self::Other(code) => return write!(f, "{code}"),
+    };
+    // 保留失败码，便于对照文档排查
+    write!(f, "{message}（{}）", self.code())
+  }
+}
+
+pub type Result<T> = std::result::Result<T, AdbError>;
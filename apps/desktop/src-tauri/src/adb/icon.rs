@@ -0,0 +1,268 @@
+use crate::adb::{
+  app::{push_user, remember_label},
+  arsc::{drawable_reference, manifest_icon, manifest_label, ResourceFile, ResourceTable},
+  command::run_device,
+  error::{AdbError, Result},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use std::{
+  fs,
+  io::{Read, Seek},
+  path::{Path, PathBuf},
+  sync::atomic::{AtomicU64, Ordering},
+};
+use zip::ZipArchive;
+
+// 资源表解析失败时按常见的启动图标文件名猜测，按优先级排列
+const ICON_NAMES: [&str; 2] = ["ic_launcher", "ic_launcher_round"];
+// 自适应图标（mipmap-anydpi-v26 下的 XML）没有位图，回退到前景层
+const FOREGROUND_NAMES: [&str; 2] = ["ic_launcher_foreground", "ic_launcher_round_foreground"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppIcon {
+  pub path: String,
+  pub mime: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub data_url: Option<String>,
+  /// 清单中的应用名，随图标一起从 APK 读出
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub label: Option<String>,
+}
+
+/// 提取应用图标并缓存到 `cache_dir/icons/<包名>_<versionCode>.<ext>`，
+/// 应用名缓存在同名的 `.label` 文件中
+pub fn app_icon(
+  device_id: &str,
+  package: &str,
+  cache_dir: &Path,
+  with_data_url: bool,
+  user_id: Option<u32>,
+) -> Result<AppIcon> {
+  let icon_dir = cache_dir.join("icons");
+  fs::create_dir_all(&icon_dir)?;
+
+  // 取不到版本号时不能用固定值代替，否则升级后缓存永远不会更新
+  let version = version_code(device_id, package, user_id)?;
+  let stem = format!("{package}_{version}");
+
+  let cached = ["png", "webp"]
+    .iter()
+    .map(|ext| icon_dir.join(format!("{stem}.{ext}")))
+    .find(|p| p.exists());
+  let path = match cached {
+    Some(path) => path,
+    None => extract_icon(device_id, package, user_id, &icon_dir, &stem)?,
+  };
+
+  let label = fs::read_to_string(icon_dir.join(format!("{stem}.label"))).ok();
+  if let Some(label) = &label {
+    remember_label(device_id, package, label);
+  }
+
+  let mime = match path.extension().and_then(|e| e.to_str()) {
+    Some("webp") => "image/webp",
+    _ => "image/png",
+  };
+  let data_url = if with_data_url {
+    Some(format!("data:{mime};base64,{}", STANDARD.encode(fs::read(&path)?)))
+  } else {
+    None
+  };
+
+  Ok(AppIcon {
+    path: path.to_string_lossy().to_string(),
+    mime: mime.to_string(),
+    data_url,
+    label,
+  })
+}
+
+fn version_code(device_id: &str, package: &str, user_id: Option<u32>) -> Result<String> {
+  let raw = run_device(device_id, &["shell", "dumpsys", "package", package])?;
+  // dumpsys package 不区分用户，按 "User 10: ... installed=true ..." 确认该用户下已安装
+  if let Some(user) = user_id {
+    let prefix = format!("User {user}:");
+    let installed = raw.lines().map(str::trim).any(|line| {
+      line.starts_with(&prefix) && line.split_whitespace().any(|f| f == "installed=true")
+    });
+    if !installed {
+      return Err(AdbError::CommandFailed(format!("用户 {user} 未安装 {package}")));
+    }
+  }
+  // versionCode=123 minSdk=24 targetSdk=34
+  raw
+    .lines()
+    .find_map(|line| line.trim().strip_prefix("versionCode="))
+    .and_then(|rest| rest.split_whitespace().next())
+    .map(|v| v.to_string())
+    .ok_or_else(|| AdbError::ParseFailed("未找到 versionCode".into()))
+}
+
+fn extract_icon(
+  device_id: &str,
+  package: &str,
+  user_id: Option<u32>,
+  icon_dir: &Path,
+  stem: &str,
+) -> Result<PathBuf> {
+  let user = user_id.map(|id| id.to_string());
+  let mut args = vec!["shell", "pm", "path"];
+  push_user(&mut args, &user);
+  args.push(package);
+  let raw = run_device(device_id, &args)?;
+  // split APK 时第一行为 base.apk，图标资源只在 base 中
+  let remote = raw
+    .lines()
+    .filter_map(|line| line.trim().strip_prefix("package:"))
+    .find(|p| p.ends_with("base.apk"))
+    .or_else(|| raw.lines().find_map(|line| line.trim().strip_prefix("package:")))
+    .ok_or_else(|| AdbError::ParseFailed(format!("未找到 {package} 的 APK 路径")))?
+    .to_string();
+
+  // 同一应用的并发请求各自拉取到不同的临时文件，避免互相覆盖或删除
+  let local_apk = icon_dir.join(format!("{stem}.{}.apk", temp_suffix()));
+  run_device(device_id, &["pull", &remote, &local_apk.to_string_lossy()])?;
+  let result = read_icon_from_apk(&local_apk, icon_dir, stem);
+  let _ = fs::remove_file(&local_apk);
+  result
+}
+
+fn read_icon_from_apk(apk: &Path, icon_dir: &Path, stem: &str) -> Result<PathBuf> {
+  let file = fs::File::open(apk)?;
+  let mut archive =
+    ZipArchive::new(file).map_err(|e| AdbError::ParseFailed(format!("APK 解析失败: {e}")))?;
+
+  if let Some(label) = resource_label(&mut archive) {
+    fs::write(icon_dir.join(format!("{stem}.label")), label)?;
+  }
+
+  let bytes = match resource_icon(&mut archive) {
+    Some(bytes) => bytes,
+    None => {
+      let names: Vec<String> = archive.file_names().map(|n| n.to_string()).collect();
+      let entry = best_icon_entry(&names, &ICON_NAMES)
+        .or_else(|| best_icon_entry(&names, &FOREGROUND_NAMES))
+        .ok_or_else(|| AdbError::ParseFailed("APK 中未找到启动图标".into()))?;
+      read_entry(&mut archive, &entry)?
+    }
+  };
+
+  let ext = if is_webp(&bytes) { "webp" } else { "png" };
+  let path = icon_dir.join(format!("{stem}.{ext}"));
+  // 先写临时文件再改名，并发读取时不会拿到写了一半的图标
+  let temp = icon_dir.join(format!("{stem}.{}.{ext}.tmp", temp_suffix()));
+  fs::write(&temp, bytes)?;
+  if let Err(e) = fs::rename(&temp, &path) {
+    let _ = fs::remove_file(&temp);
+    return Err(e.into());
+  }
+  Ok(path)
+}
+
+fn temp_suffix() -> String {
+  static COUNTER: AtomicU64 = AtomicU64::new(0);
+  format!("{}-{}", std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+fn read_entry<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<Vec<u8>> {
+  let mut bytes = Vec::new();
+  archive
+    .by_name(name)
+    .map_err(|e| AdbError::ParseFailed(format!("读取 {name} 失败: {e}")))?
+    .read_to_end(&mut bytes)?;
+  Ok(bytes)
+}
+
+fn resource_label<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Option<String> {
+  let manifest = read_entry(archive, "AndroidManifest.xml").ok()?;
+  let arsc = read_entry(archive, "resources.arsc").ok();
+  let table = arsc.as_deref().and_then(ResourceTable::parse);
+  manifest_label(&manifest, table.as_ref())
+}
+
+/// 按 AndroidManifest.xml 中 `application` 的 `android:icon`，经 resources.arsc 取密度最高的位图
+///
+/// 不依赖文件名，资源路径被混淆（如 `res/a1.png`）时同样适用。
+/// 自适应图标在 anydpi 下只有 XML，此时沿前景层引用的资源继续查找。
+fn resource_icon<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Option<Vec<u8>> {
+  let manifest = read_entry(archive, "AndroidManifest.xml").ok()?;
+  let arsc = read_entry(archive, "resources.arsc").ok()?;
+  let table = ResourceTable::parse(&arsc)?;
+  let mut id = manifest_icon(&manifest)?;
+
+  for _ in 0..3 {
+    let mut files = table.resolve_files(id);
+    files.sort_by_key(|f| std::cmp::Reverse(density_order(f)));
+    let mut drawable_xml = None;
+    for file in &files {
+      let Ok(bytes) = read_entry(archive, &file.path) else {
+        continue;
+      };
+      if is_png(&bytes) || is_webp(&bytes) {
+        return Some(bytes);
+      }
+      if drawable_xml.is_none() {
+        drawable_xml = drawable_reference(&bytes);
+      }
+    }
+    id = drawable_xml?;
+  }
+  None
+}
+
+/// 位图优先按实际密度排序；nodpi 的位图次之，anydpi（通常是 XML）最后
+fn density_order(file: &ResourceFile) -> u32 {
+  match file.density {
+    0xFFFE => 0,
+    0xFFFF => 1,
+    // 默认配置按 mdpi 处理
+    0 => 160,
+    density => u32::from(density),
+  }
+}
+
+fn is_png(bytes: &[u8]) -> bool {
+  bytes.starts_with(b"\x89PNG")
+}
+
+fn is_webp(bytes: &[u8]) -> bool {
+  bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP"
+}
+
+/// 在 `res/mipmap-*` / `res/drawable-*` 中找密度最高的同名位图
+fn best_icon_entry(names: &[String], icon_names: &[&str]) -> Option<String> {
+  names
+    .iter()
+    .filter_map(|name| {
+      let rest = name.strip_prefix("res/")?;
+      let (dir, file) = rest.split_once('/')?;
+      if !(dir.starts_with("mipmap") || dir.starts_with("drawable")) {
+        return None;
+      }
+      let (file_stem, ext) = file.rsplit_once('.')?;
+      if !matches!(ext, "png" | "webp") {
+        return None;
+      }
+      let priority = icon_names.iter().position(|n| *n == file_stem)?;
+      Some((density_rank(dir), priority, name))
+    })
+    // 密度越高越好；同密度下按文件名优先级
+    .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)))
+    .map(|(_, _, name)| name.clone())
+}
+
+fn density_rank(dir: &str) -> u32 {
+  dir
+    .split('-')
+    .find_map(|qualifier| match qualifier {
+      "xxxhdpi" => Some(640),
+      "xxhdpi" => Some(480),
+      "xhdpi" => Some(320),
+      "hdpi" => Some(240),
+      "mdpi" => Some(160),
+      "ldpi" => Some(120),
+      _ => None,
+    })
+    .unwrap_or(0)
+}
@@ -0,0 +1,613 @@
+use serde::{Deserialize, Serialize};
+
+/// 单个进程的 `dumpsys meminfo` 明细，内存单位为 MB
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryBreakdown {
+  pub pid: u32,
+  pub process: String,
+  pub java_heap_mb: Option<f64>,
+  pub native_heap_mb: Option<f64>,
+  pub code_mb: Option<f64>,
+  pub stack_mb: Option<f64>,
+  pub graphics_mb: Option<f64>,
+  pub private_other_mb: Option<f64>,
+  pub system_mb: Option<f64>,
+  pub total_pss_mb: Option<f64>,
+  pub total_rss_mb: Option<f64>,
+  pub total_swap_mb: Option<f64>,
+  pub views: Option<u64>,
+  pub view_roots: Option<u64>,
+  pub activities: Option<u64>,
+  pub app_contexts: Option<u64>,
+  pub local_binders: Option<u64>,
+  pub proxy_binders: Option<u64>,
+  pub death_recipients: Option<u64>,
+  pub webviews: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+  Table,
+  Summary,
+  Objects,
+  Other,
+}
+
+/// 解析 `dumpsys meminfo <包名|pid>` 的输出，每个 `** MEMINFO in pid ... **` 段落对应一个进程
+///
+/// 兼容的格式差异：
+/// - Android 5.x：没有 App Summary，只有明细表
+/// - Android 6.0：App Summary 只有 Pss 一列，总计为 `TOTAL: 47224      TOTAL SWAP (KB): 0`
+/// - Android 7-9：`TOTAL: 47224      TOTAL SWAP PSS: 0`
+/// - Android 10+：App Summary 增加 Rss 列，总计为 `TOTAL PSS: ... TOTAL RSS: ... TOTAL SWAP PSS: ...`
+///
+/// App Summary 缺失时，TOTAL PSS 回退到明细表中 `TOTAL` 行的第一列。
+pub fn parse_meminfo(raw: &str) -> Vec<MemoryBreakdown> {
+  let mut result = Vec::new();
+  let mut current: Option<MemoryBreakdown> = None;
+  let mut section = Section::Table;
+
+  for line in raw.lines() {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+      continue;
+    }
+
+    // ** MEMINFO in pid 1234 [com.example:remote] **
+    if let Some(rest) = trimmed.strip_prefix("** MEMINFO in pid ") {
+      result.extend(current.take());
+      let mut parts = rest.splitn(2, ' ');
+      let pid = parts.next().and_then(|p| p.parse::<u32>().ok()).unwrap_or(0);
+      let process = parts
+        .next()
+        .and_then(|n| n.split(['[', ']']).nth(1))
+        .unwrap_or_default()
+        .to_string();
+      current = Some(MemoryBreakdown {
+        pid,
+        process,
+        ..Default::default()
+      });
+      section = Section::Table;
+      continue;
+    }
+
+    let Some(entry) = current.as_mut() else {
+      continue;
+    };
+
+    match trimmed {
+      "App Summary" => {
+        section = Section::Summary;
+        continue;
+      }
+      "Objects" => {
+        section = Section::Objects;
+        continue;
+      }
+      "SQL" | "DATABASES" | "Asset Allocations" | "Unreachable memory" => {
+        section = Section::Other;
+        continue;
+      }
+      _ => {}
+    }
+
+    if trimmed.starts_with("TOTAL") {
+      if trimmed.contains(':') {
+        apply_totals(entry, trimmed);
+      } else if section == Section::Table && entry.total_pss_mb.is_none() {
+        // 明细表总计行：TOTAL  47224  30812  5020 ...，第一列为 Pss Total
+        entry.total_pss_mb = trimmed
+          .split_whitespace()
+          .nth(1)
+          .and_then(|v| v.parse::<f64>().ok())
+          .map(kb_to_mb);
+      }
+      continue;
+    }
+
+    match section {
+      Section::Summary => {
+        for (label, values) in labeled_values(trimmed) {
+          let Some(pss) = values.first().map(|v| kb_to_mb(*v as f64)) else {
+            continue;
+          };
+          match label.as_str() {
+            "Java Heap" => entry.java_heap_mb = Some(pss),
+            "Native Heap" => entry.native_heap_mb = Some(pss),
+            "Code" => entry.code_mb = Some(pss),
+            "Stack" => entry.stack_mb = Some(pss),
+            "Graphics" => entry.graphics_mb = Some(pss),
+            "Private Other" => entry.private_other_mb = Some(pss),
+            "System" => entry.system_mb = Some(pss),
+            _ => {}
+          }
+        }
+      }
+      Section::Objects => {
+        for (label, values) in labeled_values(trimmed) {
+          let Some(count) = values.first().copied() else {
+            continue;
+          };
+          match label.as_str() {
+            "Views" => entry.views = Some(count),
+            "ViewRootImpl" => entry.view_roots = Some(count),
+            "Activities" => entry.activities = Some(count),
+            "AppContexts" => entry.app_contexts = Some(count),
+            "Local Binders" => entry.local_binders = Some(count),
+            "Proxy Binders" => entry.proxy_binders = Some(count),
+            "Death Recipients" => entry.death_recipients = Some(count),
+            "WebViews" => entry.webviews = Some(count),
+            _ => {}
+          }
+        }
+      }
+      Section::Table | Section::Other => {}
+    }
+  }
+
+  result.extend(current);
+  result
+}
+
+/// App Summary 中的总计行，不同版本的写法见 [`parse_meminfo`]
+fn apply_totals(entry: &mut MemoryBreakdown, line: &str) {
+  for (label, values) in labeled_values(line) {
+    let Some(value) = values.first().map(|v| kb_to_mb(*v as f64)) else {
+      continue;
+    };
+    match label.as_str() {
+      "TOTAL" | "TOTAL PSS" => entry.total_pss_mb = Some(value),
+      "TOTAL RSS" => entry.total_rss_mb = Some(value),
+      "TOTAL SWAP PSS" | "TOTAL SWAP (KB)" => entry.total_swap_mb = Some(value),
+      _ => {}
+    }
+  }
+}
+
+/// 将 `Views: 12   ViewRootImpl: 1` 或 `Java Heap: 8932   24532` 拆成 (标签, 数值列表)
+fn labeled_values(line: &str) -> Vec<(String, Vec<u64>)> {
+  let mut result = Vec::new();
+  let mut parts = line.split(':');
+  let Some(first) = parts.next() else {
+    return result;
+  };
+  let mut label = first.trim().to_string();
+
+  for part in parts {
+    let mut values = Vec::new();
+    let mut next_label = Vec::new();
+    for token in part.split_whitespace() {
+      match token.parse::<u64>() {
+        Ok(value) if next_label.is_empty() => values.push(value),
+        _ => next_label.push(token),
+      }
+    }
+    result.push((std::mem::take(&mut label), values));
+    label = next_label.join(" ");
+  }
+
+  result
+}
+
+fn kb_to_mb(kb: f64) -> f64 {
+  kb / 1024.0
+}
+
+/// 整机内存状态，来自 /proc/meminfo 与 /proc/pressure/memory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryPressure {
+  pub total_mb: Option<f64>,
+  pub available_mb: Option<f64>,
+  pub cached_mb: Option<f64>,
+  pub swap_total_mb: Option<f64>,
+  pub swap_free_mb: Option<f64>,
+  /// 内核未开启 PSI（CONFIG_PSI）时为空
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub psi: Option<MemoryPsi>,
+  /// 距上次采样之间 lmkd 杀掉的进程
+  pub lmk_kills: Vec<LmkKill>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryPsi {
+  pub some: Option<PsiStat>,
+  pub full: Option<PsiStat>,
+}
+
+/// 一行 PSI 统计，avg 为最近 10/60/300 秒内因内存不足而停顿的时间占比（百分比）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PsiStat {
+  pub avg10: f64,
+  pub avg60: f64,
+  pub avg300: f64,
+  pub total_us: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LmkKill {
+  pub timestamp: u64, // unix timestamp in milliseconds
+  pub process: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub pid: Option<u32>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub oom_score_adj: Option<i32>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub reason: Option<String>,
+}
+
+/// 解析 `cat /proc/meminfo /proc/pressure/memory` 的合并输出
+///
+/// /proc/meminfo 的行形如 `MemAvailable:  2345678 kB`，PSI 的行以 `some` / `full` 开头。
+pub fn parse_proc_meminfo(raw: &str) -> MemoryPressure {
+  let mut pressure = MemoryPressure::default();
+  let mut psi = MemoryPsi::default();
+
+  for line in raw.lines() {
+    let line = line.trim();
+    if let Some(rest) = line.strip_prefix("some ") {
+      psi.some = parse_psi_stat(rest);
+      continue;
+    }
+    if let Some(rest) = line.strip_prefix("full ") {
+      psi.full = parse_psi_stat(rest);
+      continue;
+    }
+
+    let Some((key, value)) = line.split_once(':') else {
+      continue;
+    };
+    let Some(mb) = value
+      .split_whitespace()
+      .next()
+      .and_then(|v| v.parse::<f64>().ok())
+      .map(kb_to_mb)
+    else {
+      continue;
+    };
+    match key {
+      "MemTotal" => pressure.total_mb = Some(mb),
+      "MemAvailable" => pressure.available_mb = Some(mb),
+      "Cached" => pressure.cached_mb = Some(mb),
+      "SwapTotal" => pressure.swap_total_mb = Some(mb),
+      "SwapFree" => pressure.swap_free_mb = Some(mb),
+      _ => {}
+    }
+  }
+
+  if psi.some.is_some() || psi.full.is_some() {
+    pressure.psi = Some(psi);
+  }
+  pressure
+}
+
+// avg10=0.12 avg60=0.05 avg300=0.00 total=123456
+fn parse_psi_stat(raw: &str) -> Option<PsiStat> {
+  let mut stat = PsiStat {
+    avg10: 0.0,
+    avg60: 0.0,
+    avg300: 0.0,
+    total_us: 0,
+  };
+  let mut found = false;
+  for (key, value) in raw.split_whitespace().filter_map(|t| t.split_once('=')) {
+    match key {
+      "avg10" => stat.avg10 = value.parse().ok()?,
+      "avg60" => stat.avg60 = value.parse().ok()?,
+      "avg300" => stat.avg300 = value.parse().ok()?,
+      "total" => stat.total_us = value.parse().ok()?,
+      _ => continue,
+    }
+    found = true;
+  }
+  found.then_some(stat)
+}
+
+/// 解析 `logcat -v epoch -s lowmemorykiller` 的一行，返回 (秒级时间戳, 事件)
+///
+/// - Android 10+：`Kill 'com.example' (12345), uid 10123, oom_score_adj 900 to free 45000kB rss,
+///   0kB swap; reason: low watermark is breached and swap is low (95%)`
+/// - 更早版本：`Killing 'com.example' (12345), adj 900,`
+pub fn parse_lmk_line(line: &str) -> Option<(f64, LmkKill)> {
+  let line = line.trim();
+  let seconds = line.split_whitespace().next()?.parse::<f64>().ok()?;
+  let (_, message) = line.split_once("lowmemorykiller: ")?;
+  if !(message.starts_with("Kill '") || message.starts_with("Killing '")) {
+    return None;
+  }
+
+  let (_, rest) = message.split_once('\'')?;
+  let (process, rest) = rest.split_once('\'')?;
+  let pid = rest
+    .trim_start()
+    .strip_prefix('(')
+    .and_then(|r| r.split_once(')'))
+    .and_then(|(pid, _)| pid.parse::<u32>().ok());
+  let oom_score_adj = rest
+    .split_once("oom_score_adj ")
+    .or_else(|| rest.split_once("adj "))
+    .and_then(|(_, r)| r.split(|c: char| !(c.is_ascii_digit() || c == '-')).next())
+    .and_then(|v| v.parse::<i32>().ok());
+  let reason = rest
+    .split_once("reason: ")
+    .map(|(_, r)| r.trim().to_string())
+    .filter(|r| !r.is_empty());
+
+  let kill = LmkKill {
+    timestamp: (seconds * 1000.0) as u64,
+    process: process.to_string(),
+    pid,
+    oom_score_adj,
+    reason,
+  };
+  Some((seconds, kill))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn mb(kb: f64) -> Option<f64> {
+    Some(kb / 1024.0)
+  }
+
+  // Android 5.1：没有 App Summary，TOTAL PSS 取自明细表
+  const ANDROID_5: &str = "\
+Applications Memory Usage (kB):
+Uptime: 3518934 Realtime: 3518934
+
+** MEMINFO in pid 2345 [com.example] **
+                   Pss  Private  Private  Swapped     Heap     Heap     Heap
+                 Total    Dirty    Clean    Dirty     Size    Alloc     Free
+                ------   ------   ------   ------   ------   ------   ------
+  Native Heap     5320     5260        0        0    14336    11284     3051
+  Dalvik Heap    11210    10972        0        0    24576    18432     6144
+ Dalvik Other      812      812        0        0
+        Stack      244      244        0        0
+    Other dev        4        0        4        0
+     .so mmap     1536      348       32        0
+    .apk mmap      268        0      104        0
+    .dex mmap     2616     2596        4        0
+   Other mmap        9        4        0        0
+      Unknown      104      104        0        0
+        TOTAL    22123    20340      144        0    38912    29716     9195
+
+ Objects
+               Views:       45         ViewRootImpl:        1
+         AppContexts:        3           Activities:        1
+              Assets:        4        AssetManagers:        4
+       Local Binders:       12        Proxy Binders:       18
+       Parcel memory:        3         Parcel count:       12
+    Death Recipients:        0      OpenSSL Sockets:        0
+
+ SQL
+         MEMORY_USED:        0
+  PAGECACHE_OVERFLOW:        0          MALLOC_SIZE:        0
+";
+
+  // Android 6.0：App Summary 只有 Pss 一列
+  const ANDROID_6: &str = "\
+** MEMINFO in pid 3120 [com.example] **
+                   Pss  Private  Private  Swapped     Heap     Heap     Heap
+                 Total    Dirty    Clean    Dirty     Size    Alloc     Free
+                ------   ------   ------   ------   ------   ------   ------
+  Native Heap     5308     5260        0        0    14336    11284     3051
+  Dalvik Heap    11576    11212        0        0    24576    18432     6144
+        TOTAL    25123    21480      484        0    38912    29716     9195
+
+ App Summary
+                       Pss(KB)
+                        ------
+           Java Heap:    11576
+         Native Heap:     5260
+                Code:     4956
+               Stack:      244
+            Graphics:        0
+       Private Other:      960
+              System:     2127
+
+               TOTAL:    25123      TOTAL SWAP (KB):        0
+
+ Objects
+               Views:       62         ViewRootImpl:        1
+         AppContexts:        4           Activities:        2
+       Local Binders:       16        Proxy Binders:       23
+    Death Recipients:        1      OpenSSL Sockets:        0
+";
+
+  // Android 9：多进程，总计行为 TOTAL / TOTAL SWAP PSS
+  const ANDROID_9: &str = "\
+Applications Memory Usage (in Kilobytes):
+Uptime: 90452114 Realtime: 151038330
+
+** MEMINFO in pid 4410 [com.example] **
+                   Pss  Private  Private  SwapPss     Heap     Heap     Heap
+                 Total    Dirty    Clean    Dirty     Size    Alloc     Free
+                ------   ------   ------   ------   ------   ------   ------
+  Native Heap    18212    18168        0       36    30720    25087     5632
+  Dalvik Heap     6860     6816        0       12     9898     4949     4949
+        TOTAL    61390    36284    14136      112    40618    30036    10581
+
+ App Summary
+                       Pss(KB)
+                        ------
+           Java Heap:     9412
+         Native Heap:    18168
+                Code:    19820
+               Stack:      896
+            Graphics:     1056
+       Private Other:     1068
+              System:    10970
+
+               TOTAL:    61390       TOTAL SWAP PSS:      112
+
+ Objects
+               Views:      210         ViewRootImpl:        2
+         AppContexts:        6           Activities:        1
+              Assets:        8        AssetManagers:        0
+       Local Binders:       41        Proxy Binders:       44
+       Parcel memory:       14         Parcel count:       58
+    Death Recipients:        3      OpenSSL Sockets:        2
+            WebViews:        1
+
+ SQL
+         MEMORY_USED:      512
+  PAGECACHE_OVERFLOW:      117          MALLOC_SIZE:      117
+
+ DATABASES
+      pgsz     dbsz   Lookaside(b)          cache  Dbname
+         4       20             67         3/20/4  /data/user/0/com.example/databases/app.db
+** MEMINFO in pid 4502 [com.example:push] **
+                   Pss  Private  Private  SwapPss     Heap     Heap     Heap
+                 Total    Dirty    Clean    Dirty     Size    Alloc     Free
+                ------   ------   ------   ------   ------   ------   ------
+  Native Heap     3120     3080        0        0     8192     5400     2791
+        TOTAL    12808     6512     2276        0    11264     7180     4083
+
+ App Summary
+                       Pss(KB)
+                        ------
+           Java Heap:     1804
+         Native Heap:     3080
+                Code:     3416
+               Stack:      120
+            Graphics:        0
+       Private Other:      368
+              System:     4020
+
+               TOTAL:    12808       TOTAL SWAP PSS:        0
+
+ Objects
+               Views:        0         ViewRootImpl:        0
+         AppContexts:        2           Activities:        0
+       Local Binders:        9        Proxy Binders:       15
+    Death Recipients:        0      OpenSSL Sockets:        0
+            WebViews:        0
+";
+
+  // Android 10+：App Summary 增加 Rss 列
+  const ANDROID_10: &str = "\
+Applications Memory Usage (in Kilobytes):
+Uptime: 2411823 Realtime: 2411823
+
+** MEMINFO in pid 1234 [com.example] **
+                   Pss  Private  Private  SwapPss      Rss     Heap     Heap     Heap
+                 Total    Dirty    Clean    Dirty    Total     Size    Alloc     Free
+                ------   ------   ------   ------   ------   ------   ------   ------
+  Native Heap    14552    14500        0        0    16504    24576    18812     5763
+  Dalvik Heap     6120     6068        0        0    13796    12466     6233     6233
+        TOTAL    47224    30812    10040        0   110556    37042    25045    11996
+
+ App Summary
+                       Pss(KB)                        Rss(KB)
+                        ------                         ------
+           Java Heap:     8932                          24532
+         Native Heap:    14500                          16504
+                Code:    12216                          61784
+               Stack:      652                            660
+            Graphics:     3128                           3128
+       Private Other:     2512
+              System:     5284
+             Unknown:                                    3948
+
+           TOTAL PSS:    47224            TOTAL RSS:   110556       TOTAL SWAP PSS:        0
+
+ Objects
+               Views:      123         ViewRootImpl:        1
+         AppContexts:        5           Activities:        1
+              Assets:       20        AssetManagers:        0
+       Local Binders:       30        Proxy Binders:       40
+       Parcel memory:       11         Parcel count:       45
+    Death Recipients:        2      OpenSSL Sockets:        0
+            WebViews:        0
+
+ SQL
+         MEMORY_USED:        0
+  PAGECACHE_OVERFLOW:        0          MALLOC_SIZE:        0
+";
+
+  #[test]
+  fn android_5_falls_back_to_table_total() {
+    let result = parse_meminfo(ANDROID_5);
+    assert_eq!(result.len(), 1);
+    let entry = &result[0];
+    assert_eq!(entry.pid, 2345);
+    assert_eq!(entry.process, "com.example");
+    assert_eq!(entry.total_pss_mb, mb(22123.0));
+    assert_eq!(entry.java_heap_mb, None);
+    assert_eq!(entry.total_rss_mb, None);
+    assert_eq!(entry.views, Some(45));
+    assert_eq!(entry.view_roots, Some(1));
+    assert_eq!(entry.activities, Some(1));
+    assert_eq!(entry.local_binders, Some(12));
+    assert_eq!(entry.proxy_binders, Some(18));
+    assert_eq!(entry.death_recipients, Some(0));
+    assert_eq!(entry.webviews, None);
+  }
+
+  #[test]
+  fn android_6_single_pss_column() {
+    let result = parse_meminfo(ANDROID_6);
+    assert_eq!(result.len(), 1);
+    let entry = &result[0];
+    assert_eq!(entry.total_pss_mb, mb(25123.0));
+    assert_eq!(entry.total_swap_mb, mb(0.0));
+    assert_eq!(entry.java_heap_mb, mb(11576.0));
+    assert_eq!(entry.native_heap_mb, mb(5260.0));
+    assert_eq!(entry.code_mb, mb(4956.0));
+    assert_eq!(entry.private_other_mb, mb(960.0));
+    assert_eq!(entry.system_mb, mb(2127.0));
+    assert_eq!(entry.activities, Some(2));
+    assert_eq!(entry.app_contexts, Some(4));
+  }
+
+  #[test]
+  fn android_9_multiple_processes() {
+    let result = parse_meminfo(ANDROID_9);
+    assert_eq!(result.len(), 2);
+
+    let main = &result[0];
+    assert_eq!((main.pid, main.process.as_str()), (4410, "com.example"));
+    assert_eq!(main.total_pss_mb, mb(61390.0));
+    assert_eq!(main.total_swap_mb, mb(112.0));
+    assert_eq!(main.graphics_mb, mb(1056.0));
+    assert_eq!(main.stack_mb, mb(896.0));
+    assert_eq!(main.views, Some(210));
+    assert_eq!(main.view_roots, Some(2));
+    assert_eq!(main.webviews, Some(1));
+
+    // SQL / DATABASES 段落不会串到下一个进程
+    let push = &result[1];
+    assert_eq!((push.pid, push.process.as_str()), (4502, "com.example:push"));
+    assert_eq!(push.total_pss_mb, mb(12808.0));
+    assert_eq!(push.java_heap_mb, mb(1804.0));
+    assert_eq!(push.views, Some(0));
+    assert_eq!(push.proxy_binders, Some(15));
+  }
+
+  #[test]
+  fn android_10_pss_and_rss_columns() {
+    let result = parse_meminfo(ANDROID_10);
+    assert_eq!(result.len(), 1);
+    let entry = &result[0];
+    assert_eq!(entry.pid, 1234);
+    assert_eq!(entry.total_pss_mb, mb(47224.0));
+    assert_eq!(entry.total_rss_mb, mb(110556.0));
+    assert_eq!(entry.total_swap_mb, mb(0.0));
+    // 取 Pss 列而不是 Rss 列
+    assert_eq!(entry.java_heap_mb, mb(8932.0));
+    assert_eq!(entry.native_heap_mb, mb(14500.0));
+    assert_eq!(entry.code_mb, mb(12216.0));
+    assert_eq!(entry.graphics_mb, mb(3128.0));
+    assert_eq!(entry.system_mb, mb(5284.0));
+    assert_eq!(entry.views, Some(123));
+    assert_eq!(entry.app_contexts, Some(5));
+    assert_eq!(entry.death_recipients, Some(2));
+    assert_eq!(entry.webviews, Some(0));
+  }
+
+  #[test]
+  fn no_meminfo_section() {
+    assert!(parse_meminfo("No process found for: com.example").is_empty());
+  }
+}
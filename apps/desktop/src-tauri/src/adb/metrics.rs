@@ -1,21 +1,38 @@
 use crate::adb::{
+  app::{current_foreground, push_user},
   command::run_device,
   error::{AdbError, Result},
+  meminfo::{parse_lmk_line, parse_meminfo, parse_proc_meminfo, MemoryBreakdown, MemoryPressure},
+  thermal::{fetch_thermal, ThermalStats},
 };
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone)]
 struct FpsHistory {
   total_frames: u64,
   timestamp: u64, // unix timestamp in milliseconds
+  last_vsync_id: u64, // 上次读到的最后一帧，用于 framestats 去重
+  last_frame_completed: u64,
+  recent_intervals: Vec<f64>, // 最近 3 帧的帧间隔，用于跨采样判定卡顿
+  reset: bool, // 上次读取后是否已 reset，此时 total_frames 即为窗口内的帧数
 }
 
 static FPS_HISTORY: Lazy<Mutex<HashMap<String, FpsHistory>>> = Lazy::new(|| Mutex::new(HashMap::new()));
 
+#[derive(Debug, Clone)]
+struct SfFrameHistory {
+  last_present: u64, // 上次读到的最后一帧上屏时间（纳秒）
+  timestamp: u64,    // unix timestamp in milliseconds
+  recent_intervals: Vec<f64>,
+}
+
+static SF_FRAME_HISTORY: Lazy<Mutex<HashMap<String, SfFrameHistory>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
 #[derive(Debug, Clone)]
 struct TrafficHistory {
   rx_bytes: u64,
@@ -26,6 +43,115 @@ struct TrafficHistory {
 static TRAFFIC_HISTORY: Lazy<Mutex<HashMap<String, TrafficHistory>>> =
   Lazy::new(|| Mutex::new(HashMap::new()));
 
+#[derive(Debug, Clone)]
+struct NetworkHistory {
+  interfaces: HashMap<String, (u64, u64)>, // 接口名 -> (rx_bytes, tx_bytes)
+  timestamp: u64,
+}
+
+// 整机网络：按设备记录上一次各接口的累计字节数
+static NETWORK_HISTORY: Lazy<Mutex<HashMap<String, NetworkHistory>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone)]
+struct CpuHistory {
+  process_jiffies: u64, // utime + stime
+  total_jiffies: u64,   // /proc/stat 中 cpu 行的总和
+}
+
+static CPU_HISTORY: Lazy<Mutex<HashMap<String, CpuHistory>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone)]
+struct ThreadCpuHistory {
+  total_jiffies: u64,
+  threads: HashMap<u32, u64>, // tid -> utime + stime
+}
+
+static THREAD_CPU_HISTORY: Lazy<Mutex<HashMap<String, ThreadCpuHistory>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 整机 CPU：按设备记录上一次 /proc/stat 中各 cpu 行的累计时间
+static SYSTEM_CPU_HISTORY: Lazy<Mutex<HashMap<String, HashMap<String, CpuTimes>>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+// lmkd 查杀事件：按设备记录已读取到的最后一条日志时间（秒），0 表示此前没有记录
+static LMK_HISTORY: Lazy<Mutex<HashMap<String, f64>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone)]
+struct PowerHistory {
+  power_mw: f64,
+  energy_mwh: f64,
+  timestamp: u64, // unix timestamp in milliseconds
+}
+
+// 整机功耗：按设备记录上一次采样，用于累计本次会话的电能
+static POWER_HISTORY: Lazy<Mutex<HashMap<String, PowerHistory>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 按设备记录已确定的 current_now 单位，避免小电流时按阈值误判
+static CURRENT_UNIT: Lazy<Mutex<HashMap<String, CurrentUnit>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 刷新率只在切换显示模式时变化，短时间内复用上次 dumpsys display 的结果
+const REFRESH_RATE_TTL: Duration = Duration::from_secs(5);
+
+// 按设备缓存屏幕刷新率及读取时间
+static REFRESH_RATE_CACHE: Lazy<Mutex<HashMap<String, (f64, Instant)>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone)]
+struct ForegroundHistory {
+  target: String,                  // 当前跟随的包名
+  last_foreground: Option<String>, // 上次采样时的前台应用，只有它变化时才切换目标
+  launcher: Option<String>,        // 桌面应用的包名，回到桌面不算应用切换
+}
+
+// 自动跟随模式下的跟随状态，按 设备:初始包名 区分会话
+static FOREGROUND_HISTORY: Lazy<Mutex<HashMap<String, ForegroundHistory>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CollectOptions {
+  /// 前台应用切换时自动把采集目标切换到新的前台应用
+  #[serde(default)]
+  pub follow_foreground: bool,
+  /// 线程 CPU 指标返回占用最高的线程数
+  #[serde(default = "default_thread_top_n")]
+  pub thread_top_n: usize,
+  /// FPS 数据来源
+  #[serde(default)]
+  pub fps_source: FpsSource,
+  /// 每次读取 gfxinfo 后执行 reset，使帧数与百分位只反映本次采样窗口
+  #[serde(default)]
+  pub gfxinfo_reset: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FpsSource {
+  /// `dumpsys gfxinfo`，只统计 hwui 绘制的帧
+  #[default]
+  Gfxinfo,
+  /// `dumpsys SurfaceFlinger --latency`，适用于游戏、Flutter、SurfaceView 等
+  SurfaceFlinger,
+}
+
+impl Default for CollectOptions {
+  fn default() -> Self {
+    CollectOptions {
+      follow_foreground: false,
+      thread_top_n: default_thread_top_n(),
+      fps_source: FpsSource::default(),
+      gfxinfo_reset: false,
+    }
+  }
+}
+
+fn default_thread_top_n() -> usize {
+  10
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum MetricKey {
@@ -37,23 +163,306 @@ pub enum MetricKey {
   Battery,
   BatteryTemp,
   Traffic,
+  SystemCpu,
+  ThreadCpu,
+  CpuFreq,
+  MemoryPressure,
+  Gpu,
+  Thermal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FpsStatus {
+  Ok,
+  /// 首次采样或采样间隔过短，尚无基准
+  WarmingUp,
+  Unavailable,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FrameStats {
   pub fps: f64,
   pub avg_frame_time: f64, // 平均帧耗时（毫秒）
-  pub frame_times: Vec<f64>, // 最近的帧耗时数组
-  pub jank_count: u32, // 帧率不稳定的次数
+  pub frame_times: Vec<f64>, // 上次采样以来每一帧的耗时（毫秒）
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub p90_frame_time: Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub p95_frame_time: Option<f64>,
+  pub jank: u32,
+  pub big_jank: u32, // 同时计入 jank
+  pub stutter: f64, // 卡顿时长占比（百分比）
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub layer: Option<String>, // SurfaceFlinger 来源时实际统计的图层
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub reset: Option<bool>, // gfxinfo 来源时，本次读取后是否已 reset 计数
+}
+
+// 卡顿阈值：电影帧（24fps）的 2 帧 / 3 帧耗时
+const JANK_MIN_MS: f64 = 84.0;
+const BIG_JANK_MIN_MS: f64 = 125.0;
+
+#[derive(Debug, Clone, Default)]
+struct JankStats {
+  jank: u32,
+  big_jank: u32,
+  stutter: f64,
+}
+
+/// framestats 中的一帧，时间均为 CLOCK_MONOTONIC 纳秒
+#[derive(Debug, Clone)]
+struct FrameTiming {
+  vsync_id: u64, // FrameTimelineVsyncId，旧版本没有该列时为 IntendedVsync
+  intended_vsync: u64,
+  frame_completed: u64,
+}
+
+impl FrameTiming {
+  fn duration_ms(&self) -> f64 {
+    self.frame_completed.saturating_sub(self.intended_vsync) as f64 / 1_000_000.0
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessMetrics {
+  pub pid: u32,
+  pub name: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub uid: Option<u32>,
+  pub cpu: Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub cpu_normalized: Option<f64>,
+  pub memory_mb: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoreUsage {
+  pub core: u32,
+  pub usage: f64, // 百分比
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CpuFreqInfo {
+  pub core: u32,
+  pub online: bool,
+  pub cur_khz: Option<u64>,
+  pub min_khz: Option<u64>, // scaling_min_freq
+  pub max_khz: Option<u64>, // scaling_max_freq，受温控/省电策略限制
+  pub hw_max_khz: Option<u64>, // cpuinfo_max_freq，硬件上限
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub governor: Option<String>,
+  #[serde(skip)]
+  related_cpus: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuCluster {
+  pub id: usize,
+  pub cores: Vec<u32>,
+  pub cur_khz: Option<u64>, // 簇内在线核心的最高频率
+  pub max_khz: Option<u64>,
+  pub hw_max_khz: Option<u64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub governor: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GpuSource {
+  /// 高通 /sys/class/kgsl/kgsl-3d0
+  Adreno,
+  /// ARM /sys/class/misc/mali0
+  Mali,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuStats {
+  pub source: GpuSource,
+  pub usage: Option<f64>, // 百分比
+  pub cur_mhz: Option<u64>,
+  pub min_mhz: Option<u64>,
+  pub max_mhz: Option<u64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub governor: Option<String>,
+}
+
+/// `dumpsys battery` 中 status 的取值（BatteryManager.BATTERY_STATUS_*）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryStatus {
+  Unknown,
+  Charging,
+  Discharging,
+  NotCharging,
+  Full,
+}
+
+impl BatteryStatus {
+  fn from_code(code: u32) -> Self {
+    match code {
+      2 => BatteryStatus::Charging,
+      3 => BatteryStatus::Discharging,
+      4 => BatteryStatus::NotCharging,
+      5 => BatteryStatus::Full,
+      _ => BatteryStatus::Unknown,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryPlugged {
+  Ac,
+  Usb,
+  Wireless,
+  Dock,
+}
+
+/// `dumpsys battery` 中 health 的取值（BatteryManager.BATTERY_HEALTH_*）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryHealth {
+  Unknown,
+  Good,
+  Overheat,
+  Dead,
+  OverVoltage,
+  Failure,
+  Cold,
+}
+
+impl BatteryHealth {
+  fn from_code(code: u32) -> Self {
+    match code {
+      2 => BatteryHealth::Good,
+      3 => BatteryHealth::Overheat,
+      4 => BatteryHealth::Dead,
+      5 => BatteryHealth::OverVoltage,
+      6 => BatteryHealth::Failure,
+      7 => BatteryHealth::Cold,
+      _ => BatteryHealth::Unknown,
+    }
+  }
+}
+
+/// power_supply 节点原始数值的单位，不同厂商内核不一致
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CurrentUnit {
+  Microamp,
+  Milliamp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoltageUnit {
+  Microvolt,
+  Millivolt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerStats {
+  pub power_mw: f64,   // 放电为正，充电为负
+  pub current_ma: f64, // 放电为正，充电为负
+  pub voltage_mv: f64,
+  pub current_unit: CurrentUnit,
+  pub voltage_unit: VoltageUnit,
+  pub energy_mwh: f64, // 本次会话累计消耗的电能
+  pub charging: bool,  // 接着外部电源，此时功耗数值不代表真实耗电
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkKind {
+  Wifi,
+  Cellular,
+  Other, // 有线网卡、USB 共享等
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceTraffic {
+  pub name: String,
+  pub kind: NetworkKind,
+  pub rx_bytes: u64,
+  pub tx_bytes: u64,
+  pub rx_bps: Option<f64>, // 字节/秒，首次采样或计数器重置时为空
+  pub tx_bps: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetworkStats {
+  pub rx_bps: Option<f64>,
+  pub tx_bps: Option<f64>,
+  pub wifi_rx_bps: Option<f64>,
+  pub wifi_tx_bps: Option<f64>,
+  pub cellular_rx_bps: Option<f64>,
+  pub cellular_tx_bps: Option<f64>,
+  pub interfaces: Vec<InterfaceTraffic>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadCpu {
+  pub tid: u32,
+  pub name: String,
+  pub cpu: f64, // 以单核为 100%
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForegroundSwitch {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub from: Option<String>,
+  pub to: String,
+  pub timestamp: u64, // unix timestamp in milliseconds
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct MetricsSnapshot {
+  /// 本次实际采集的包名（自动跟随模式下可能与请求的不同）
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub package: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub foreground_switch: Option<ForegroundSwitch>,
   pub fps: Option<f64>,
-  pub cpu: Option<f64>,
-  pub power: Option<f64>,
-  pub memory_mb: Option<f64>,
-  pub network_kbps: Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub fps_source: Option<FpsSource>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub fps_status: Option<FpsStatus>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub fps_unavailable: Option<String>, // 无法获取帧率的原因
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub refresh_rate: Option<f64>, // 屏幕当前刷新率（Hz）
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub fps_normalized: Option<f64>, // 帧率占刷新率的百分比
+  pub cpu: Option<f64>, // 应用全部进程之和，以单核为 100%
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub cpu_normalized: Option<f64>, // 以全部核心为 100%
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub system_cpu: Option<f64>, // 整机 CPU 使用率
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub cpu_cores: Option<Vec<CoreUsage>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub threads: Option<Vec<ThreadCpu>>, // 主进程中 CPU 占用最高的线程
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub cpu_freq: Option<Vec<CpuFreqInfo>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub cpu_clusters: Option<Vec<CpuCluster>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub gpu: Option<GpuStats>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub gpu_unavailable: Option<String>, // 无法读取 GPU 数据的原因
+  pub power: Option<f64>, // mW
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub power_stats: Option<PowerStats>,
+  pub memory_mb: Option<f64>, // 应用全部进程之和
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub memory_detail: Option<Vec<MemoryBreakdown>>, // 每个进程的 dumpsys meminfo 明细
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub memory_pressure: Option<MemoryPressure>, // 整机内存压力
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub processes: Option<Vec<ProcessMetrics>>,
+  pub network_kbps: Option<f64>, // KB/s
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub network: Option<NetworkStats>, // 整机各网络接口的速率
   #[serde(skip_serializing_if = "Option::is_none")]
   pub network_bps: Option<f64>,
   #[serde(skip_serializing_if = "Option::is_none")]
@@ -69,15 +478,70 @@ pub struct MetricsSnapshot {
   #[serde(skip_serializing_if = "Option::is_none")]
   pub battery_temp_c: Option<f64>,
   #[serde(skip_serializing_if = "Option::is_none")]
+  pub battery_voltage_mv: Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub battery_current_ma: Option<f64>, // 放电为正，充电为负
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub battery_status: Option<BatteryStatus>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub battery_plugged: Option<BatteryPlugged>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub battery_health: Option<BatteryHealth>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub battery_charge_counter_mah: Option<f64>,
+  /// 采样时接着外部电源，功耗相关数据应视为无效
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub charging: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub thermal: Option<ThermalStats>,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub frame_stats: Option<FrameStats>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub raw: Option<String>,
 }
 
+#[derive(Debug, Clone)]
+struct AppProcess {
+  pid: u32,
+  uid: Option<u32>,
+  name: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CpuUsage {
+  percent: f64,    // 以单核为 100%
+  normalized: f64, // 以全部核心为 100%
+}
+
+/// /proc/stat 中一行 cpu 的累计时间（单位 jiffies）
+#[derive(Debug, Clone, Copy)]
+struct CpuTimes {
+  total: u64,
+  idle: u64, // idle + iowait
+}
+
 #[derive(Debug, Clone)]
 struct BatteryStats {
   level: Option<f64>,
   temp_c: Option<f64>,
+  voltage_mv: Option<f64>,
+  current_ma: Option<f64>,
+  status: Option<BatteryStatus>,
+  plugged: Option<BatteryPlugged>,
+  health: Option<BatteryHealth>,
+  charge_counter_mah: Option<f64>,
+}
+
+impl BatteryStats {
+  /// 接着外部电源时功耗数据不可信（包括充满或暂停充电的情况）
+  fn external_power(&self) -> Option<bool> {
+    match (self.plugged, self.status) {
+      (Some(_), _) => Some(true),
+      (None, Some(BatteryStatus::Charging | BatteryStatus::Full)) => Some(true),
+      (None, Some(_)) => Some(false),
+      (None, None) => None,
+    }
+  }
 }
 
 #[derive(Debug, Clone)]
@@ -107,27 +571,132 @@ pub fn collect_metrics(
   device_id: &str,
   package: &str,
   metrics: &[MetricKey],
+  user_id: Option<u32>,
+  options: &CollectOptions,
 ) -> Result<MetricsSnapshot> {
   let mut snapshot = MetricsSnapshot::default();
-  let need_pid = metrics
+  let target = if options.follow_foreground {
+    let (target, switch) = follow_foreground(device_id, package);
+    snapshot.foreground_switch = switch;
+    target
+  } else {
+    package.to_string()
+  };
+  let package = target.as_str();
+  snapshot.package = Some(target.clone());
+  // 指定用户时，gfxinfo 需要按 pid 定位到该用户下的进程
+  let need_processes = metrics.iter().any(|m| match m {
+    MetricKey::Cpu | MetricKey::Memory | MetricKey::Traffic => true,
+    MetricKey::ThreadCpu => true,
+    MetricKey::Fps => user_id.is_some(),
+    _ => false,
+  });
+  let processes = if need_processes {
+    resolve_processes(device_id, package, user_id).unwrap_or_default()
+  } else {
+    Vec::new()
+  };
+  // 主进程（进程名与包名一致），用于只需要单个进程的指标
+  let pid = processes
     .iter()
-    .any(|m| matches!(m, MetricKey::Cpu | MetricKey::Traffic));
-  let pid = if need_pid { resolve_pid(device_id, package).ok() } else { None };
+    .find(|p| p.name == package)
+    .or_else(|| processes.first())
+    .map(|p| p.pid.to_string());
+  let mut process_metrics: Vec<ProcessMetrics> = processes
+    .iter()
+    .map(|p| ProcessMetrics {
+      pid: p.pid,
+      name: p.name.clone(),
+      uid: p.uid,
+      cpu: None,
+      cpu_normalized: None,
+      memory_mb: None,
+    })
+    .collect();
   let mut battery_stats: Option<BatteryStats> = None;
   let mut traffic_stats: Option<TrafficStats> = None;
 
   for metric in metrics {
     match metric {
       MetricKey::Cpu => {
+        // 首次采样没有基准，不输出数值
+        if let Ok(usage) = fetch_cpu(device_id, &processes) {
+          for entry in process_metrics.iter_mut() {
+            if let Some(cpu) = usage.get(&entry.pid) {
+              entry.cpu = Some(cpu.percent);
+              entry.cpu_normalized = Some(cpu.normalized);
+            }
+          }
+          if !usage.is_empty() {
+            snapshot.cpu = Some(usage.values().map(|c| c.percent).sum());
+            snapshot.cpu_normalized = Some(usage.values().map(|c| c.normalized).sum());
+          }
+        }
+      }
+      MetricKey::ThreadCpu => {
         if let Some(ref pid) = pid {
-          snapshot.cpu = fetch_cpu(device_id, pid).ok();
+          snapshot.threads = fetch_thread_cpu(device_id, pid, options.thread_top_n)
+            .ok()
+            .filter(|threads| !threads.is_empty());
+        }
+      }
+      MetricKey::CpuFreq => {
+        if let Ok(cores) = fetch_cpu_freq(device_id) {
+          snapshot.cpu_clusters = Some(group_clusters(&cores));
+          snapshot.cpu_freq = Some(cores);
+        }
+      }
+      MetricKey::Gpu => match fetch_gpu(device_id) {
+        Ok(gpu) => snapshot.gpu = Some(gpu),
+        Err(AdbError::ParseFailed(reason)) => snapshot.gpu_unavailable = Some(reason),
+        Err(e) => snapshot.gpu_unavailable = Some(e.to_string()),
+      },
+      MetricKey::SystemCpu => {
+        if let Ok((total, cores)) = fetch_system_cpu(device_id) {
+          snapshot.system_cpu = total;
+          snapshot.cpu_cores = Some(cores).filter(|c| !c.is_empty());
         }
       }
       MetricKey::Memory => {
-        snapshot.memory_mb = fetch_memory(device_id, package).ok();
+        if let Ok(mut usage) = fetch_memory(device_id, package, &processes) {
+          // 未解析到进程时按包名查询，会包含所有用户下的同名进程
+          if user_id.is_some() {
+            usage.retain(|m| processes.iter().any(|p| p.pid == m.pid));
+          }
+          for detail in &usage {
+            let memory_mb = detail.total_pss_mb;
+            match process_metrics.iter_mut().find(|p| p.pid == detail.pid) {
+              Some(entry) => entry.memory_mb = memory_mb,
+              None => process_metrics.push(ProcessMetrics {
+                pid: detail.pid,
+                name: if detail.process.is_empty() {
+                  package.to_string()
+                } else {
+                  detail.process.clone()
+                },
+                uid: None,
+                cpu: None,
+                cpu_normalized: None,
+                memory_mb,
+              }),
+            }
+          }
+          snapshot.memory_mb = Some(usage.iter().filter_map(|m| m.total_pss_mb).sum());
+          snapshot.memory_detail = Some(usage);
+        }
+      }
+      MetricKey::MemoryPressure => {
+        snapshot.memory_pressure = fetch_memory_pressure(device_id).ok();
       }
       MetricKey::Network => {
-        snapshot.network_kbps = fetch_network(device_id).ok();
+        if let Ok(network) = fetch_network(device_id) {
+          let total = match (network.rx_bps, network.tx_bps) {
+            (None, None) => None,
+            (rx, tx) => Some(rx.unwrap_or(0.0) + tx.unwrap_or(0.0)),
+          };
+          snapshot.network_kbps = total.map(|bps| bps / 1024.0);
+          snapshot.network = Some(network);
+        }
       }
       MetricKey::Traffic => {
         if traffic_stats.is_none() {
@@ -145,13 +714,46 @@ pub fn collect_metrics(
         }
       }
       MetricKey::Fps => {
-        if let Ok(frame_stats) = fetch_fps(device_id, package) {
-          snapshot.fps = Some(frame_stats.fps);
-          snapshot.frame_stats = Some(frame_stats);
+        let target = match (&pid, user_id) {
+          (Some(pid), Some(_)) => pid.as_str(),
+          _ => package,
+        };
+        let frame_stats = match options.fps_source {
+          FpsSource::Gfxinfo => fetch_fps(device_id, target, options.gfxinfo_reset),
+          FpsSource::SurfaceFlinger => fetch_fps_surfaceflinger(device_id, package).map(Some),
+        };
+        snapshot.fps_source = Some(options.fps_source);
+        let reason = match frame_stats {
+          Ok(Some(frame_stats)) => {
+            let refresh_rate = fetch_refresh_rate(device_id).ok();
+            snapshot.fps = Some(frame_stats.fps);
+            snapshot.fps_status = Some(FpsStatus::Ok);
+            snapshot.refresh_rate = refresh_rate;
+            snapshot.fps_normalized = refresh_rate.map(|rate| frame_stats.fps / rate * 100.0);
+            snapshot.frame_stats = Some(frame_stats);
+            None
+          }
+          Ok(None) => {
+            snapshot.fps_status = Some(FpsStatus::WarmingUp);
+            None
+          }
+          Err(AdbError::ParseFailed(reason)) => Some(reason),
+          Err(e) => Some(e.to_string()),
+        };
+        if reason.is_some() {
+          snapshot.fps_status = Some(FpsStatus::Unavailable);
+          snapshot.fps_unavailable = reason;
         }
       }
       MetricKey::Power => {
-        snapshot.power = fetch_power(device_id, package).ok();
+        if let Ok(power) = fetch_power(device_id) {
+          snapshot.power = Some(power.power_mw);
+          snapshot.charging = Some(power.charging);
+          snapshot.power_stats = Some(power);
+        }
+      }
+      MetricKey::Thermal => {
+        snapshot.thermal = fetch_thermal(device_id).ok();
       }
       MetricKey::Battery | MetricKey::BatteryTemp => {
         if battery_stats.is_none() {
@@ -160,116 +762,926 @@ pub fn collect_metrics(
         if let Some(ref battery) = battery_stats {
           snapshot.battery_level = battery.level;
           snapshot.battery_temp_c = battery.temp_c;
+          snapshot.battery_voltage_mv = battery.voltage_mv;
+          snapshot.battery_current_ma = battery.current_ma;
+          snapshot.battery_status = battery.status;
+          snapshot.battery_plugged = battery.plugged;
+          snapshot.battery_health = battery.health;
+          snapshot.battery_charge_counter_mah = battery.charge_counter_mah;
+          snapshot.charging = battery.external_power().or(snapshot.charging);
         }
       }
     }
   }
 
+  if metrics.iter().any(|m| matches!(m, MetricKey::Cpu | MetricKey::Memory))
+    && !process_metrics.is_empty()
+  {
+    snapshot.processes = Some(process_metrics);
+  }
+
   Ok(snapshot)
 }
 
-fn resolve_pid(device_id: &str, package: &str) -> Result<String> {
-  let raw = run_device(device_id, &["shell", "pidof", package])?;
-  raw.split_whitespace()
-    .next()
-    .map(|s| s.to_string())
-    .ok_or_else(|| AdbError::ParseFailed("未找到进程".into()))
+/// 开始新的采集会话时调用，清空上一次会话遗留的跟随状态等基准数据
+pub fn reset_session(device_id: &str, package: &str) {
+  let key = format!("{device_id}:{package}");
+  if let Ok(mut history) = FOREGROUND_HISTORY.lock() {
+    history.remove(&key);
+  }
+  // 帧率基准：否则新会话第一次采样会沿用上个会话的累计帧数，得到接近 0 的帧率。
+  // 多用户时 gfxinfo 按 pid 查询，会话开始时还不知道新的 pid，一并清除该设备按 pid 记录的基准
+  if let Ok(mut history) = FPS_HISTORY.lock() {
+    history.retain(|k, _| k != &key && !is_pid_key(k, device_id));
+  }
+  if let Ok(mut history) = SF_FRAME_HISTORY.lock() {
+    history.remove(&key);
+  }
+  // CPU 占用按两次采样的 jiffies 差计算，旧基准会让第一次采样变成跨会话的平均值
+  if let Ok(mut history) = CPU_HISTORY.lock() {
+    history.retain(|k, _| !is_pid_key(k, device_id));
+  }
+  if let Ok(mut history) = THREAD_CPU_HISTORY.lock() {
+    history.retain(|k, _| !is_pid_key(k, device_id));
+  }
+  if let Ok(mut history) = SYSTEM_CPU_HISTORY.lock() {
+    history.remove(device_id);
+  }
+  // 网络速率同样按字节数差计算
+  if let Ok(mut history) = TRAFFIC_HISTORY.lock() {
+    history.retain(|k, _| !is_pid_key(k, device_id));
+  }
+  if let Ok(mut history) = NETWORK_HISTORY.lock() {
+    history.remove(device_id);
+  }
+  // 查杀事件与累计电能只统计本次会话开始之后的
+  if let Ok(mut history) = LMK_HISTORY.lock() {
+    history.remove(device_id);
+  }
+  if let Ok(mut history) = POWER_HISTORY.lock() {
+    history.remove(device_id);
+  }
 }
 
-fn fetch_cpu(device_id: &str, pid: &str) -> Result<f64> {
-  let raw = run_device(device_id, &["shell", "top", "-b", "-n", "1", "-q", "-p", pid])?;
-  for line in raw.lines() {
-    let parts: Vec<&str> = line.split_whitespace().collect();
-    // top 命令输出格式通常是: PID USER PR NI VIRT RES SHR S %CPU %MEM TIME+ ARGS
-    if parts.len() >= 9 && parts[0] == pid {
-      if let Some(cpu_str) = parts.get(8) {
-        if let Some(value) = cpu_str.parse::<f64>().ok() {
-          // 确保 CPU 使用率不超过 100%
-          return Ok(value.min(100.0));
-        }
-      }
-    }
-  }
-  Err(AdbError::ParseFailed("CPU 解析失败".into()))
+// `设备:pid` 形式的键
+fn is_pid_key(key: &str, device_id: &str) -> bool {
+  key
+    .strip_prefix(device_id)
+    .and_then(|rest| rest.strip_prefix(':'))
+    .is_some_and(|pid| !pid.is_empty() && pid.bytes().all(|b| b.is_ascii_digit()))
 }
 
-fn fetch_memory(device_id: &str, package: &str) -> Result<f64> {
-  let raw = run_device(device_id, &["shell", "dumpsys", "meminfo", package])?;
-  for line in raw.lines() {
-    if line.contains("TOTAL") {
-      if let Some(value) = line
-        .split_whitespace()
-        .filter_map(|t| t.parse::<f64>().ok())
-        .next()
-      {
-        return Ok(value / 1024.0); // 转换为 MB
-      }
+/// 返回应跟随的包名；前台应用变化时同时返回一次切换事件
+///
+/// 会话的第一次采样只记录当前前台应用作为基准，即使它不是目标应用也不切换。
+fn follow_foreground(device_id: &str, package: &str) -> (String, Option<ForegroundSwitch>) {
+  let key = format!("{device_id}:{package}");
+  // 只在读写状态时加锁，避免某台设备的 adb 调用卡住其他设备的采样
+  let cached = FOREGROUND_HISTORY.lock().ok().and_then(|history| history.get(&key).cloned());
+  let mut state = cached.unwrap_or_else(|| ForegroundHistory {
+    target: package.to_string(),
+    last_foreground: None,
+    launcher: resolve_launcher(device_id),
+  });
+
+  let foreground = match current_foreground(device_id) {
+    // 下拉通知栏等系统界面、回到桌面不算应用切换
+    Ok(app)
+      if app.package != "com.android.systemui"
+        && state.launcher.as_deref() != Some(app.package.as_str()) =>
+    {
+      Some(app.package)
     }
-  }
-  Err(AdbError::ParseFailed("内存解析失败".into()))
-}
+    _ => None,
+  };
 
-fn fetch_network(device_id: &str) -> Result<f64> {
-  let raw = run_device(device_id, &["shell", "cat", "/proc/net/dev"])?;
-  for line in raw.lines() {
-    if line.contains("wlan0") || line.contains("rmnet") {
-      let parts: Vec<&str> = line.split_whitespace().collect();
-      if parts.len() >= 17 {
-        let rx: f64 = parts[1].parse().unwrap_or(0.0);
-        let tx: f64 = parts[9].parse().unwrap_or(0.0);
-        // 粗略展示为 kbps（单次采样无法得出速率，此处仅返回累计 KB）
-        return Ok((rx + tx) / 1024.0);
-      }
+  let mut switch = None;
+  if let Some(foreground) = foreground {
+    let previous = state.last_foreground.replace(foreground.clone());
+    if previous.is_some_and(|p| p != foreground) && foreground != state.target {
+      switch = Some(ForegroundSwitch {
+        from: Some(state.target.clone()),
+        to: foreground.clone(),
+        timestamp: SystemTime::now()
+          .duration_since(UNIX_EPOCH)
+          .unwrap_or_default()
+          .as_millis() as u64,
+      });
+      state.target = foreground;
     }
   }
-  Err(AdbError::ParseFailed("网络解析失败".into()))
+
+  let target = state.target.clone();
+  if let Ok(mut history) = FOREGROUND_HISTORY.lock() {
+    history.insert(key, state);
+  }
+  (target, switch)
 }
 
-fn fetch_fps(device_id: &str, package: &str) -> Result<FrameStats> {
-  let raw = run_device(device_id, &["shell", "dumpsys", "gfxinfo", package])?;
+/// 查询默认桌面应用的包名，Android 7.0 以下没有 `cmd package` 时返回 None
+fn resolve_launcher(device_id: &str) -> Option<String> {
+  let raw = run_device(
+    device_id,
+    &[
+      "shell",
+      "cmd",
+      "package",
+      "resolve-activity",
+      "--brief",
+      "-a",
+      "android.intent.action.MAIN",
+      "-c",
+      "android.intent.category.HOME",
+    ],
+  )
+  .ok()?;
+  // priority=0 preferredOrder=0 match=0x108000 specificIndex=-1 isDefault=true
+  // com.google.android.apps.nexuslauncher/.NexusLauncherActivity
+  raw
+    .lines()
+    .rev()
+    .find_map(|line| line.trim().split_once('/'))
+    .map(|(pkg, _)| pkg.to_string())
+    .filter(|pkg| !pkg.is_empty() && pkg != "android")
+}
 
-  let mut total_frames = None;
-  let mut janky_frames = None;
-  let mut percentile_90th = None;
-  let mut percentile_95th = None;
+// Process.FIRST_APPLICATION_UID
+const FIRST_APPLICATION_UID: u32 = 10_000;
+// Process.FIRST_APP_ZYGOTE_ISOLATED_UID，其后直到 99999 都是隔离进程的 uid
+const FIRST_ISOLATED_UID: u32 = 90_000;
 
-  // 解析 dumpsys gfxinfo 的输出
-  for line in raw.lines() {
-    let line = line.trim();
+/// 列出应用的全部进程（含 :remote、:push 等子进程）
+fn resolve_processes(
+  device_id: &str,
+  package: &str,
+  user_id: Option<u32>,
+) -> Result<Vec<AppProcess>> {
+  let app_id = resolve_app_id(device_id, package, user_id).ok();
+  let raw = run_device(device_id, &["shell", "ps", "-A", "-o", "PID,UID,NAME"]).unwrap_or_default();
+  let mut processes = Vec::new();
+  let mut sandboxed = Vec::new();
 
-    // 提取总帧数
-    if let Some(total_str) = line.strip_prefix("Total frames rendered:") {
-      if let Ok(total) = total_str.trim().parse::<u64>() {
-        total_frames = Some(total);
-      }
+  for line in raw.lines().skip(1) {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 3 {
+      continue;
+    }
+    let (Ok(pid), Ok(uid)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>()) else {
+      continue;
+    };
+    let name = parts[2];
+    if user_id.is_some_and(|user| user != uid / 100_000) {
+      continue;
     }
 
-    // 提取卡顿帧数
-    if let Some(janky_str) = line.strip_prefix("Janky frames:") {
-      // 格式可能是 "Janky frames: 50 (4.17%)"，我们只需要数字部分
-      if let Some(num_str) = janky_str.split('(').next() {
-        if let Ok(janky) = num_str.trim().parse::<u32>() {
-          janky_frames = Some(janky);
-        }
-      }
+    // 多用户下 uid = userId * 100000 + appId；appId 小于 10000 的是共享的系统 uid
+    // （如 android.uid.system 对应 system_server），不能按 uid 归属。
+    // 应用自己声明的 isolatedProcess 服务 uid 不同，但进程名以包名开头，按名称匹配。
+    let same_app = app_id.is_some_and(|id| id >= FIRST_APPLICATION_UID && uid % 100_000 == id);
+    let named_after = name == package || name.starts_with(&format!("{package}:"));
+    let process = AppProcess {
+      pid,
+      uid: Some(uid),
+      name: name.to_string(),
+    };
+    if same_app || named_after {
+      processes.push(process);
+    } else if uid % 100_000 >= FIRST_ISOLATED_UID && name.contains(":sandboxed_process") {
+      sandboxed.push(process);
     }
+  }
 
-    // 提取90th百分位数
-    if let Some(p90_str) = line.strip_prefix("90th percentile:") {
-      if let Some(ms_str) = p90_str.strip_suffix("ms") {
-        if let Ok(p90) = ms_str.trim().parse::<f64>() {
-          percentile_90th = Some(p90);
-        }
-      }
+  if !processes.is_empty() {
+    if !sandboxed.is_empty() {
+      let attributed = attribute_sandboxed(device_id, &processes, sandboxed);
+      processes.extend(attributed);
     }
+    return Ok(processes);
+  }
 
-    // 提取95th百分位数
-    if let Some(p95_str) = line.strip_prefix("95th percentile:") {
-      if let Some(ms_str) = p95_str.strip_suffix("ms") {
-        if let Ok(p95) = ms_str.trim().parse::<f64>() {
-          percentile_95th = Some(p95);
+  // 旧系统的 ps 不支持 -A/-o，回退到 pidof（仅能拿到主进程）
+  let raw = run_device(device_id, &["shell", "pidof", package])?;
+  let processes: Vec<AppProcess> = raw
+    .split_whitespace()
+    .filter_map(|s| s.parse::<u32>().ok())
+    .map(|pid| AppProcess {
+      pid,
+      uid: None,
+      name: package.to_string(),
+    })
+    .collect();
+
+  if processes.is_empty() {
+    return Err(AdbError::ParseFailed("未找到进程".into()));
+  }
+  Ok(processes)
+}
+
+/// 找出由应用发起的 WebView 渲染进程
+///
+/// 渲染进程属于 WebView 提供方（如 `com.google.android.webview:sandboxed_process0:...`），
+/// 使用独立的隔离 uid，只能通过 `dumpsys activity services` 中绑定该服务的客户端进程归属到应用。
+fn attribute_sandboxed(
+  device_id: &str,
+  app: &[AppProcess],
+  sandboxed: Vec<AppProcess>,
+) -> Vec<AppProcess> {
+  let app_pids: HashSet<u32> = app.iter().map(|p| p.pid).collect();
+  // 按提供方包名过滤服务，缩小 dumpsys 的输出
+  let mut providers: Vec<&str> =
+    sandboxed.iter().filter_map(|p| p.name.split(':').next()).collect();
+  providers.sort_unstable();
+  providers.dedup();
+
+  let mut hosts = HashSet::new();
+  for provider in providers {
+    let Ok(raw) = run_device(device_id, &["shell", "dumpsys", "activity", "services", provider])
+    else {
+      continue;
+    };
+    for (host, clients) in parse_service_clients(&raw) {
+      if clients.iter().any(|pid| app_pids.contains(pid)) {
+        hosts.insert(host);
+      }
+    }
+  }
+
+  sandboxed.into_iter().filter(|p| hosts.contains(&p.pid)).collect()
+}
+
+/// 解析 `dumpsys activity services`，返回每个服务所在进程及绑定它的客户端进程：
+///
+/// ```text
+/// * ServiceRecord{1a2b u0 com.google.android.webview/org.chromium...SandboxedProcessService0}
+///   app=ProcessRecord{3c4d 12345:com.google.android.webview:sandboxed_process0:.../u0i12}
+///   * Client AppBindRecord{5e6f ProcessRecord{7a8b 4410:com.example/u0a123}}
+/// ```
+fn parse_service_clients(raw: &str) -> Vec<(u32, Vec<u32>)> {
+  let mut result = Vec::new();
+  let mut host: Option<u32> = None;
+  let mut clients = Vec::new();
+
+  for line in raw.lines() {
+    let line = line.trim();
+    if line.starts_with("* ServiceRecord{") {
+      if let Some(host) = host.take() {
+        result.push((host, std::mem::take(&mut clients)));
+      }
+      clients.clear();
+    } else if let Some(rest) = line.strip_prefix("app=") {
+      host = process_record_pid(rest);
+    } else if line.starts_with("* Client AppBindRecord{") {
+      clients.extend(process_record_pid(line));
+    }
+  }
+  if let Some(host) = host {
+    result.push((host, clients));
+  }
+  result
+}
+
+// ProcessRecord{7a8b 4410:com.example/u0a123}
+fn process_record_pid(text: &str) -> Option<u32> {
+  let (_, rest) = text.split_once("ProcessRecord{")?;
+  rest.split_whitespace().nth(1)?.split(':').next()?.parse().ok()
+}
+
+/// 获取应用的 appId（不含用户偏移的 uid）
+fn resolve_app_id(device_id: &str, package: &str, user_id: Option<u32>) -> Result<u32> {
+  let user = user_id.map(|id| id.to_string());
+  let mut args = vec!["shell", "pm", "list", "packages", "-U"];
+  push_user(&mut args, &user);
+  args.push(package);
+  let raw = run_device(device_id, &args)?;
+  // package:com.example uid:10123
+  raw
+    .lines()
+    .filter_map(|line| line.trim().strip_prefix("package:"))
+    .filter_map(|rest| rest.split_once(" uid:"))
+    .find(|(pkg, _)| *pkg == package)
+    .and_then(|(_, uid)| uid.split(',').next()?.trim().parse::<u32>().ok())
+    .map(|uid| uid % 100_000)
+    .ok_or_else(|| AdbError::ParseFailed("未找到应用 uid".into()))
+}
+
+/// 根据 /proc/<pid>/stat 与 /proc/stat 两次采样之间的 jiffies 差值计算 CPU 使用率
+fn fetch_cpu(device_id: &str, processes: &[AppProcess]) -> Result<HashMap<u32, CpuUsage>> {
+  if processes.is_empty() {
+    return Err(AdbError::ParseFailed("未找到进程".into()));
+  }
+
+  // 一次 shell 调用读取全部文件；进程可能已退出，忽略其错误
+  let stat_paths: Vec<String> = processes
+    .iter()
+    .map(|p| format!("/proc/{}/stat", p.pid))
+    .collect();
+  let mut args = vec!["shell", "cat", "/proc/stat"];
+  args.extend(stat_paths.iter().map(String::as_str));
+  args.extend(["2>/dev/null", "||", "true"]);
+  let raw = run_device(device_id, &args)?;
+
+  let mut total: Option<CpuTimes> = None;
+  let mut cores = 0usize;
+  let mut process_jiffies: HashMap<u32, u64> = HashMap::new();
+  for line in raw.lines() {
+    if let Some((label, times)) = parse_cpu_line(line) {
+      if label == "cpu" {
+        total = Some(times);
+      } else {
+        cores += 1;
+      }
+    } else if let Some((pid, _, jiffies)) = parse_task_stat(line) {
+      process_jiffies.insert(pid, jiffies);
+    }
+  }
+
+  let total = total.ok_or_else(|| AdbError::ParseFailed("CPU 解析失败".into()))?;
+  let cores = cores.max(1) as f64;
+  let mut usage = HashMap::new();
+
+  if let Ok(mut history) = CPU_HISTORY.lock() {
+    for (pid, jiffies) in process_jiffies {
+      let key = format!("{device_id}:{pid}");
+      if let Some(prev) = history.get(&key) {
+        let total_diff = total.total.saturating_sub(prev.total_jiffies);
+        if total_diff > 0 {
+          let proc_diff = jiffies.saturating_sub(prev.process_jiffies) as f64;
+          let normalized = proc_diff * 100.0 / total_diff as f64;
+          usage.insert(
+            pid,
+            CpuUsage {
+              percent: normalized * cores,
+              normalized,
+            },
+          );
         }
       }
+
+      history.insert(
+        key,
+        CpuHistory {
+          process_jiffies: jiffies,
+          total_jiffies: total.total,
+        },
+      );
+    }
+  }
+
+  Ok(usage)
+}
+
+/// 主进程内各线程的 CPU 使用率，按占用降序返回前 `top_n` 个
+fn fetch_thread_cpu(device_id: &str, pid: &str, top_n: usize) -> Result<Vec<ThreadCpu>> {
+  // task/*/stat 中的 comm 与 task/*/comm 内容一致（最长 15 字节），无需再单独读取
+  let tasks = format!("/proc/{pid}/task/*/stat");
+  let raw = run_device(
+    device_id,
+    &["shell", "cat", "/proc/stat", &tasks, "2>/dev/null", "||", "true"],
+  )?;
+
+  let mut total: Option<u64> = None;
+  let mut cores = 0usize;
+  let mut threads: HashMap<u32, (String, u64)> = HashMap::new();
+  for line in raw.lines() {
+    if let Some((label, times)) = parse_cpu_line(line) {
+      if label == "cpu" {
+        total = Some(times.total);
+      } else {
+        cores += 1;
+      }
+    } else if let Some((tid, comm, jiffies)) = parse_task_stat(line) {
+      threads.insert(tid, (comm.to_string(), jiffies));
+    }
+  }
+
+  let total = total.ok_or_else(|| AdbError::ParseFailed("CPU 解析失败".into()))?;
+  if threads.is_empty() {
+    return Err(AdbError::ParseFailed("未读取到线程信息".into()));
+  }
+  let cores = cores.max(1) as f64;
+  let key = format!("{device_id}:{pid}");
+  let mut result = Vec::new();
+
+  if let Ok(mut history) = THREAD_CPU_HISTORY.lock() {
+    if let Some(prev) = history.get(&key) {
+      let total_diff = total.saturating_sub(prev.total_jiffies);
+      if total_diff > 0 {
+        for (tid, (name, jiffies)) in &threads {
+          // 新创建的线程以 0 为基准
+          let prev_jiffies = prev.threads.get(tid).copied().unwrap_or(0);
+          let diff = jiffies.saturating_sub(prev_jiffies) as f64;
+          result.push(ThreadCpu {
+            tid: *tid,
+            name: name.clone(),
+            cpu: diff * 100.0 * cores / total_diff as f64,
+          });
+        }
+      }
+    }
+
+    history.insert(
+      key,
+      ThreadCpuHistory {
+        total_jiffies: total,
+        threads: threads.into_iter().map(|(tid, (_, j))| (tid, j)).collect(),
+      },
+    );
+  }
+
+  result.sort_by(|a, b| b.cpu.total_cmp(&a.cpu));
+  result.truncate(top_n);
+  Ok(result)
+}
+
+/// 读取 /sys/devices/system/cpu 下各核心的频率与调频策略
+fn fetch_cpu_freq(device_id: &str) -> Result<Vec<CpuFreqInfo>> {
+  const FILES: [&str; 7] = [
+    "online",
+    "cpufreq/scaling_cur_freq",
+    "cpufreq/scaling_min_freq",
+    "cpufreq/scaling_max_freq",
+    "cpufreq/cpuinfo_max_freq",
+    "cpufreq/scaling_governor",
+    "cpufreq/related_cpus",
+  ];
+  let globs: Vec<String> = FILES.iter().map(|f| format!("cpu[0-9]*/{f}")).collect();
+  let mut args = vec!["shell", "cd", "/sys/devices/system/cpu", "&&", "grep", "-H", "."];
+  args.extend(globs.iter().map(String::as_str));
+  args.extend(["2>/dev/null", "||", "true"]);
+  let raw = run_device(device_id, &args)?;
+
+  // 输出形如 cpu4/cpufreq/scaling_cur_freq:1804800
+  let mut cores: HashMap<u32, CpuFreqInfo> = HashMap::new();
+  for line in raw.lines() {
+    let Some((path, value)) = line.trim().split_once(':') else {
+      continue;
+    };
+    let Some((cpu, file)) = path.split_once('/') else {
+      continue;
+    };
+    let Some(core) = cpu.strip_prefix("cpu").and_then(|id| id.parse::<u32>().ok()) else {
+      continue;
+    };
+
+    let entry = cores.entry(core).or_insert_with(|| CpuFreqInfo {
+      core,
+      // cpu0 通常没有 online 文件，且总是在线
+      online: true,
+      ..Default::default()
+    });
+    let value = value.trim();
+    match file {
+      "online" => entry.online = value == "1",
+      "cpufreq/scaling_cur_freq" => entry.cur_khz = value.parse().ok(),
+      "cpufreq/scaling_min_freq" => entry.min_khz = value.parse().ok(),
+      "cpufreq/scaling_max_freq" => entry.max_khz = value.parse().ok(),
+      "cpufreq/cpuinfo_max_freq" => entry.hw_max_khz = value.parse().ok(),
+      "cpufreq/scaling_governor" => entry.governor = Some(value.to_string()),
+      "cpufreq/related_cpus" => entry.related_cpus = Some(value.to_string()),
+      _ => {}
+    }
+  }
+
+  if cores.is_empty() {
+    return Err(AdbError::ParseFailed("未读取到 CPU 频率信息".into()));
+  }
+
+  let mut cores: Vec<CpuFreqInfo> = cores.into_values().collect();
+  cores.sort_by_key(|c| c.core);
+  Ok(cores)
+}
+
+/// 按 related_cpus 划分大小核簇；读不到时按硬件最高频率划分
+fn group_clusters(cores: &[CpuFreqInfo]) -> Vec<CpuCluster> {
+  let mut clusters: Vec<(String, CpuCluster)> = Vec::new();
+
+  for info in cores {
+    let key = info
+      .related_cpus
+      .clone()
+      .or_else(|| info.hw_max_khz.map(|f| format!("max:{f}")))
+      .unwrap_or_else(|| format!("cpu:{}", info.core));
+    let online_cur = info.cur_khz.filter(|_| info.online);
+
+    match clusters.iter_mut().find(|(k, _)| *k == key) {
+      Some((_, cluster)) => {
+        cluster.cores.push(info.core);
+        cluster.cur_khz = cluster.cur_khz.max(online_cur);
+        cluster.max_khz = cluster.max_khz.or(info.max_khz);
+        cluster.hw_max_khz = cluster.hw_max_khz.or(info.hw_max_khz);
+        cluster.governor = cluster.governor.clone().or_else(|| info.governor.clone());
+      }
+      None => {
+        let cluster = CpuCluster {
+          id: clusters.len(),
+          cores: vec![info.core],
+          cur_khz: online_cur,
+          max_khz: info.max_khz,
+          hw_max_khz: info.hw_max_khz,
+          governor: info.governor.clone(),
+        };
+        clusters.push((key, cluster));
+      }
+    }
+  }
+
+  clusters.into_iter().map(|(_, cluster)| cluster).collect()
+}
+
+// GPU 节点，不同厂商/内核版本只会存在其中一部分
+const KGSL_DIR: &str = "/sys/class/kgsl/kgsl-3d0";
+const MALI_DIR: &str = "/sys/class/misc/mali0";
+const GPU_NODES: [&str; 12] = [
+  "/sys/class/kgsl/kgsl-3d0/gpu_busy_percentage",
+  "/sys/class/kgsl/kgsl-3d0/gpubusy",
+  "/sys/class/kgsl/kgsl-3d0/devfreq/cur_freq",
+  "/sys/class/kgsl/kgsl-3d0/devfreq/min_freq",
+  "/sys/class/kgsl/kgsl-3d0/devfreq/max_freq",
+  "/sys/class/kgsl/kgsl-3d0/devfreq/governor",
+  "/sys/class/misc/mali0/device/utilization",
+  "/sys/class/misc/mali0/device/devfreq/*/cur_freq",
+  "/sys/class/misc/mali0/device/devfreq/*/min_freq",
+  "/sys/class/misc/mali0/device/devfreq/*/max_freq",
+  "/sys/class/misc/mali0/device/devfreq/*/governor",
+  "/sys/class/devfreq/*.mali/cur_freq",
+];
+
+/// 读取 Adreno / Mali 的 GPU 使用率与频率，读不到时返回 ParseFailed 并附带原因
+fn fetch_gpu(device_id: &str) -> Result<GpuStats> {
+  // 先列出存在的 GPU 目录，用于区分“没有该 GPU”与“节点不可读”
+  let mut args = vec!["shell", "ls", "-d", KGSL_DIR, MALI_DIR, "2>/dev/null", ";"];
+  args.extend(["grep", "-H", "."]);
+  args.extend(GPU_NODES);
+  args.extend(["2>/dev/null", "||", "true"]);
+  let raw = run_device(device_id, &args)?;
+  parse_gpu_nodes(&raw)
+}
+
+fn parse_gpu_nodes(raw: &str) -> Result<GpuStats> {
+  let mut dirs = Vec::new();
+  let mut values: Vec<(&str, &str)> = Vec::new();
+  for line in raw.lines().map(str::trim) {
+    match line.split_once(':') {
+      Some((path, value)) => values.push((path, value.trim())),
+      None if !line.is_empty() => dirs.push(line),
+      None => {}
+    }
+  }
+
+  let source = if values.iter().any(|(path, _)| path.starts_with(KGSL_DIR)) {
+    GpuSource::Adreno
+  } else if values.iter().any(|(path, _)| path.contains("mali")) {
+    GpuSource::Mali
+  } else if dirs.is_empty() {
+    return Err(AdbError::ParseFailed("未找到 Adreno/Mali GPU 节点".into()));
+  } else {
+    return Err(AdbError::ParseFailed(format!(
+      "GPU 节点不可读（可能受 SELinux 限制）: {}",
+      dirs.join(", ")
+    )));
+  };
+
+  let mut stats = GpuStats {
+    source,
+    usage: None,
+    cur_mhz: None,
+    min_mhz: None,
+    max_mhz: None,
+    governor: None,
+  };
+  // 部分 Mali 驱动输出 gpu/cljs0/cljs1=45/0/0，取等号后的第一个数
+  let first_number = |value: &str| {
+    value
+      .rsplit('=')
+      .next()
+      .unwrap_or(value)
+      .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+      .find(|t| !t.is_empty())
+      .and_then(|t| t.parse::<f64>().ok())
+  };
+  // devfreq 中的频率单位为 Hz
+  let to_mhz = |value: &str| value.parse::<u64>().ok().map(|hz| hz / 1_000_000);
+
+  for (path, value) in values {
+    let is_source = match source {
+      GpuSource::Adreno => path.starts_with(KGSL_DIR),
+      GpuSource::Mali => path.contains("mali"),
+    };
+    if !is_source {
+      continue;
+    }
+    let file = path.rsplit('/').next().unwrap_or_default();
+    match file {
+      // 45 %
+      "gpu_busy_percentage" => stats.usage = first_number(value),
+      // busy total：最近一个采样窗口内的忙碌/总时间，空闲时均为 0
+      "gpubusy" if stats.usage.is_none() => {
+        let mut parts = value.split_whitespace().filter_map(|v| v.parse::<f64>().ok());
+        if let (Some(busy), Some(total)) = (parts.next(), parts.next()) {
+          stats.usage = Some(if total > 0.0 { busy / total * 100.0 } else { 0.0 });
+        }
+      }
+      "utilization" => stats.usage = first_number(value),
+      "cur_freq" => stats.cur_mhz = stats.cur_mhz.or(to_mhz(value)),
+      "min_freq" => stats.min_mhz = to_mhz(value),
+      "max_freq" => stats.max_mhz = to_mhz(value),
+      "governor" => stats.governor = Some(value.to_string()),
+      _ => {}
+    }
+  }
+  stats.usage = stats.usage.map(|u| u.clamp(0.0, 100.0));
+
+  if stats.usage.is_none() && stats.cur_mhz.is_none() {
+    return Err(AdbError::ParseFailed(format!("{source:?} GPU 节点中没有可用数据")));
+  }
+  Ok(stats)
+}
+
+/// 整机与各核心的 CPU 使用率；首次采样或核心刚上线时没有基准，不输出该项
+fn fetch_system_cpu(device_id: &str) -> Result<(Option<f64>, Vec<CoreUsage>)> {
+  let raw = run_device(device_id, &["shell", "cat", "/proc/stat"])?;
+  let current: HashMap<String, CpuTimes> = raw
+    .lines()
+    .filter_map(parse_cpu_line)
+    .map(|(label, times)| (label.to_string(), times))
+    .collect();
+  if !current.contains_key("cpu") {
+    return Err(AdbError::ParseFailed("CPU 解析失败".into()));
+  }
+
+  let usage = |prev: &CpuTimes, now: &CpuTimes| {
+    let total = now.total.saturating_sub(prev.total);
+    let idle = now.idle.saturating_sub(prev.idle);
+    (total > 0).then(|| total.saturating_sub(idle) as f64 * 100.0 / total as f64)
+  };
+
+  let mut total = None;
+  let mut cores = Vec::new();
+  if let Ok(mut history) = SYSTEM_CPU_HISTORY.lock() {
+    if let Some(prev) = history.get(device_id) {
+      for (label, now) in &current {
+        let Some(value) = prev.get(label).and_then(|p| usage(p, now)) else {
+          continue;
+        };
+        match label.strip_prefix("cpu").filter(|id| !id.is_empty()) {
+          Some(id) => {
+            if let Ok(core) = id.parse::<u32>() {
+              cores.push(CoreUsage { core, usage: value });
+            }
+          }
+          None => total = Some(value),
+        }
+      }
+    }
+    history.insert(device_id.to_string(), current);
+  }
+
+  cores.sort_by_key(|c| c.core);
+  Ok((total, cores))
+}
+
+/// 解析 /proc/stat 中的 `cpu` / `cpuN` 行
+fn parse_cpu_line(line: &str) -> Option<(&str, CpuTimes)> {
+  let mut parts = line.split_whitespace();
+  let label = parts.next().filter(|l| l.starts_with("cpu"))?;
+  // user nice system idle iowait irq softirq steal；guest 已计入 user，不再重复累加
+  let values: Vec<u64> = parts.take(8).filter_map(|v| v.parse().ok()).collect();
+  if values.len() < 4 {
+    return None;
+  }
+  let idle = values[3] + values.get(4).copied().unwrap_or(0);
+  Some((
+    label,
+    CpuTimes {
+      total: values.iter().sum(),
+      idle,
+    },
+  ))
+}
+
+/// 解析 /proc/<pid>/stat 或 /proc/<pid>/task/<tid>/stat，返回 (id, comm, utime + stime)
+fn parse_task_stat(line: &str) -> Option<(u32, &str, u64)> {
+  // comm 可能包含空格和括号，以第一个 '(' 与最后一个 ')' 为界
+  let (head, tail) = line.rsplit_once(')')?;
+  let (id, comm) = head.split_once('(')?;
+  let id = id.trim().parse::<u32>().ok()?;
+  let fields: Vec<&str> = tail.split_whitespace().collect();
+  // ')' 之后依次为 state(3) ppid(4) ... utime(14) stime(15)
+  let utime = fields.get(11)?.parse::<u64>().ok()?;
+  let stime = fields.get(12)?.parse::<u64>().ok()?;
+  Some((id, comm, utime + stime))
+}
+
+/// 返回应用每个进程的 `dumpsys meminfo` 明细（App Summary、TOTAL PSS/RSS/SWAP 与 Objects）
+///
+/// `dumpsys meminfo <包名>` 只会输出第一个匹配的进程，因此按 pid 逐个查询（含 WebView 渲染进程），
+/// 在一次 shell 调用里完成；未解析到进程时退回 `--package`。
+fn fetch_memory(
+  device_id: &str,
+  package: &str,
+  processes: &[AppProcess],
+) -> Result<Vec<MemoryBreakdown>> {
+  let pids: Vec<String> = processes.iter().map(|p| p.pid.to_string()).collect();
+  let mut args = vec!["shell"];
+  if pids.is_empty() {
+    args.extend(["dumpsys", "meminfo", "--package", package]);
+  }
+  for (index, pid) in pids.iter().enumerate() {
+    if index > 0 {
+      args.push(";");
+    }
+    args.extend(["dumpsys", "meminfo", pid.as_str()]);
+  }
+  let raw = run_device(device_id, &args)?;
+  // 多进程时每个进程一段：** MEMINFO in pid 1234 [com.example:remote] **
+  let usage: Vec<MemoryBreakdown> = parse_meminfo(&raw)
+    .into_iter()
+    .filter(|m| m.pid != 0 && m.total_pss_mb.is_some())
+    .collect();
+
+  if usage.is_empty() {
+    return Err(AdbError::ParseFailed("内存解析失败".into()));
+  }
+  Ok(usage)
+}
+
+/// 整机可用内存、PSI 以及自上次采样以来的 lmkd 查杀事件
+fn fetch_memory_pressure(device_id: &str) -> Result<MemoryPressure> {
+  // 内核未开启 PSI 时没有 /proc/pressure/memory
+  let raw = run_device(
+    device_id,
+    &["shell", "cat", "/proc/meminfo", "/proc/pressure/memory", "2>/dev/null", "||", "true"],
+  )?;
+  let mut pressure = parse_proc_meminfo(&raw);
+  if pressure.total_mb.is_none() {
+    return Err(AdbError::ParseFailed("未读取到 /proc/meminfo".into()));
+  }
+
+  let since = LMK_HISTORY.lock().ok().and_then(|history| history.get(device_id).copied());
+  let since_arg = since.filter(|s| *s > 0.0).map(|s| format!("{s:.3}"));
+  let mut args = vec!["shell", "logcat", "-d", "-v", "epoch", "-s", "lowmemorykiller"];
+  if let Some(ref since) = since_arg {
+    args.extend(["-t", since.as_str()]);
+  }
+  let Ok(raw) = run_device(device_id, &args) else {
+    return Ok(pressure);
+  };
+
+  let mut latest = since.unwrap_or(0.0);
+  for (seconds, kill) in raw.lines().filter_map(parse_lmk_line) {
+    // 首次采样只记录基准，之前的查杀与本次会话无关；-t 的起始时间是包含在内的
+    if since.is_some_and(|s| seconds > s) {
+      pressure.lmk_kills.push(kill);
+    }
+    latest = latest.max(seconds);
+  }
+  if let Ok(mut history) = LMK_HISTORY.lock() {
+    history.insert(device_id.to_string(), latest);
+  }
+
+  Ok(pressure)
+}
+
+/// 整机网络速率：汇总 /proc/net/dev 中的 Wi-Fi、蜂窝等外网接口，按接口分别计算
+fn fetch_network(device_id: &str) -> Result<NetworkStats> {
+  let raw = run_device(device_id, &["shell", "cat", "/proc/net/dev"])?;
+  let mut counters: Vec<(String, NetworkKind, u64, u64)> = Vec::new();
+
+  for line in raw.lines() {
+    // wlan0: 123456 789 0 0 0 0 0 0 654321 ...（冒号后可能没有空格）
+    let Some((iface, payload)) = line.trim().split_once(':') else {
+      continue;
+    };
+    let iface = iface.trim();
+    let Some(kind) = network_kind(iface) else {
+      continue;
+    };
+    let cols: Vec<u64> = payload.split_whitespace().filter_map(|c| c.parse().ok()).collect();
+    // 跳过从未有过流量的接口（如空闲的 rmnet_data*）
+    if cols.len() >= 16 && (cols[0] > 0 || cols[8] > 0) {
+      counters.push((iface.to_string(), kind, cols[0], cols[8]));
+    }
+  }
+
+  if counters.is_empty() {
+    return Err(AdbError::ParseFailed("未找到可用网络接口".into()));
+  }
+
+  let now = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap_or_default()
+    .as_millis() as u64;
+  let mut history = NETWORK_HISTORY
+    .lock()
+    .map_err(|_| AdbError::ParseFailed("网络历史记录不可用".into()))?;
+  let prev = history.get(device_id);
+  let dt_ms = prev.map(|p| now.saturating_sub(p.timestamp).max(1) as f64);
+
+  let mut stats = NetworkStats::default();
+  for (name, kind, rx_bytes, tx_bytes) in &counters {
+    // 接口重连后计数器会清零，此时本次不计算速率
+    let prev_bytes = prev.and_then(|p| p.interfaces.get(name));
+    let rate = |current: u64, previous: Option<u64>| {
+      let diff = current.checked_sub(previous?)?;
+      Some(diff as f64 * 1000.0 / dt_ms?)
+    };
+    let rx_bps = rate(*rx_bytes, prev_bytes.map(|p| p.0));
+    let tx_bps = rate(*tx_bytes, prev_bytes.map(|p| p.1));
+
+    let add = |total: &mut Option<f64>, value: Option<f64>| {
+      if let Some(value) = value {
+        *total = Some(total.unwrap_or(0.0) + value);
+      }
+    };
+    add(&mut stats.rx_bps, rx_bps);
+    add(&mut stats.tx_bps, tx_bps);
+    match kind {
+      NetworkKind::Wifi => {
+        add(&mut stats.wifi_rx_bps, rx_bps);
+        add(&mut stats.wifi_tx_bps, tx_bps);
+      }
+      NetworkKind::Cellular => {
+        add(&mut stats.cellular_rx_bps, rx_bps);
+        add(&mut stats.cellular_tx_bps, tx_bps);
+      }
+      NetworkKind::Other => {}
+    }
+
+    stats.interfaces.push(InterfaceTraffic {
+      name: name.clone(),
+      kind: *kind,
+      rx_bytes: *rx_bytes,
+      tx_bytes: *tx_bytes,
+      rx_bps,
+      tx_bps,
+    });
+  }
+
+  history.insert(
+    device_id.to_string(),
+    NetworkHistory {
+      interfaces: counters.into_iter().map(|(name, _, rx, tx)| (name, (rx, tx))).collect(),
+      timestamp: now,
+    },
+  );
+
+  Ok(stats)
+}
+
+/// 按接口名区分网络类型；lo、VPN(tun)、clat(v4-*) 以及 rmnet_ipa 等汇总接口
+/// 的流量会与物理接口重复，不计入
+fn network_kind(iface: &str) -> Option<NetworkKind> {
+  if iface.starts_with("wlan") {
+    Some(NetworkKind::Wifi)
+  } else if iface.starts_with("rmnet_ipa") || iface.starts_with("rmnet_mhi") {
+    None
+  } else if ["rmnet", "ccmni", "pdp", "cell", "seth"].iter().any(|p| iface.starts_with(p)) {
+    Some(NetworkKind::Cellular)
+  } else if iface.starts_with("eth") || iface.starts_with("usb") || iface.starts_with("rndis") {
+    Some(NetworkKind::Other)
+  } else {
+    None
+  }
+}
+
+/// 读取 gfxinfo 帧数据；首次采样或距上次不足 100ms 时没有可用的帧率，返回 `None`
+///
+/// `target` 为包名或 pid（dumpsys gfxinfo 两者均支持）。
+/// `reset` 时读取与清零在同一次 `dumpsys gfxinfo <target> framestats reset` 中完成，
+/// 两次采样之间的帧不会遗漏，窗口即为上次 reset 到本次 reset 的时间。
+fn fetch_fps(device_id: &str, target: &str, reset: bool) -> Result<Option<FrameStats>> {
+  let key = format!("{}:{}", device_id, target);
+  let prev = FPS_HISTORY
+    .lock()
+    .map_err(|_| AdbError::ParseFailed("FPS 历史记录不可用".into()))?
+    .get(&key)
+    .cloned();
+  // 至少间隔100ms，否则保留原有基准，等下次采样；需在读取前判断，否则 reset 会丢掉这段帧
+  let started = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap()
+    .as_millis() as u64;
+  if prev.as_ref().is_some_and(|p| started < p.timestamp + 100) {
+    return Ok(None);
+  }
+
+  let mut args = vec!["shell", "dumpsys", "gfxinfo", target, "framestats"];
+  if reset {
+    args.push("reset");
+  }
+  let raw = run_device(device_id, &args)?;
+
+  let mut total_frames = None;
+
+  // 解析 dumpsys gfxinfo 的输出
+  for line in raw.lines() {
+    let line = line.trim();
+
+    // 提取总帧数
+    if let Some(total_str) = line.strip_prefix("Total frames rendered:") {
+      if let Ok(total) = total_str.trim().parse::<u64>() {
+        total_frames = Some(total);
+      }
     }
   }
 
@@ -285,129 +1697,502 @@ fn fetch_fps(device_id: &str, package: &str) -> Result<FrameStats> {
     .as_millis() as u64;
 
   // 计算FPS（基于历史数据）
-  let key = format!("{}:{}", device_id, package);
-  let fps = if let Ok(history) = FPS_HISTORY.lock() {
-    if let Some(prev) = history.get(&key) {
-      let time_diff_sec = (now - prev.timestamp) as f64 / 1000.0;
-      if time_diff_sec > 0.1 { // 至少间隔100ms
-        let frame_diff = total_frames.saturating_sub(prev.total_frames);
-        (frame_diff as f64) / time_diff_sec
-      } else {
-        // 时间间隔太短，使用估算值
-        60.0
-      }
+  let fps = prev.as_ref().map(|prev| {
+    let time_diff_sec = (now - prev.timestamp) as f64 / 1000.0;
+    let frame_diff = if prev.reset {
+      total_frames
     } else {
-      // 第一次采样，使用估算值
-      60.0
-    }
-  } else {
-    60.0
-  };
+      total_frames.saturating_sub(prev.total_frames)
+    };
+    (frame_diff as f64) / time_diff_sec
+  });
+
+  // 只保留上次采样之后的新帧；首次采样时使用缓冲区中的全部帧
+  let last_vsync_id = prev.as_ref().map(|p| p.last_vsync_id).unwrap_or(0);
+  let frames: Vec<FrameTiming> = parse_framestats(&raw)
+    .into_iter()
+    .filter(|f| f.vsync_id > last_vsync_id)
+    .collect();
+
+  // 帧间隔：本帧完成时间 - max(上一帧完成时间, 本帧期望开始时间)，
+  // 这样画面静止后的第一帧不会因为空闲时间被算作卡顿
+  let mut last_completed = prev.as_ref().map(|p| p.last_frame_completed).unwrap_or(0);
+  let intervals: Vec<f64> = frames
+    .iter()
+    .map(|f| {
+      let start = if last_completed > 0 {
+        last_completed.max(f.intended_vsync)
+      } else {
+        f.intended_vsync
+      };
+      last_completed = f.frame_completed;
+      f.frame_completed.saturating_sub(start) as f64 / 1_000_000.0
+    })
+    .collect();
+  let mut recent_intervals = prev.map(|p| p.recent_intervals).unwrap_or_default();
+  let jank = classify_jank(&mut recent_intervals, &intervals);
 
   // 更新历史记录
   if let Ok(mut history) = FPS_HISTORY.lock() {
     history.insert(key, FpsHistory {
       total_frames,
       timestamp: now,
+      last_vsync_id: frames.last().map(|f| f.vsync_id).unwrap_or(last_vsync_id),
+      last_frame_completed: last_completed,
+      recent_intervals,
+      reset,
+    });
+  }
+
+  // 第一次采样只记录基准
+  let Some(fps) = fps else {
+    return Ok(None);
+  };
+
+  let frame_times: Vec<f64> = frames.iter().map(FrameTiming::duration_ms).collect();
+  let avg_frame_time = if frame_times.is_empty() {
+    0.0
+  } else {
+    frame_times.iter().sum::<f64>() / frame_times.len() as f64
+  };
+
+  Ok(Some(FrameStats {
+    fps,
+    avg_frame_time,
+    p90_frame_time: percentile(&frame_times, 90.0),
+    p95_frame_time: percentile(&frame_times, 95.0),
+    frame_times,
+    jank: jank.jank,
+    big_jank: jank.big_jank,
+    stutter: jank.stutter,
+    layer: None,
+    reset: Some(reset),
+  }))
+}
+
+/// 解析 `dumpsys gfxinfo <包名> framestats` 中的 PROFILEDATA 段，按 vsync 去重并排序
+///
+/// 列名随版本变化（Android 12 起增加 FrameTimelineVsyncId 等列），因此按表头定位。
+/// Flags 非 0 的帧（如窗口尺寸变化后的首帧）不计入。
+fn parse_framestats(raw: &str) -> Vec<FrameTiming> {
+  let mut frames: Vec<FrameTiming> = Vec::new();
+  let mut columns: Option<(usize, Option<usize>, usize, usize)> = None;
+
+  for line in raw.lines().map(str::trim) {
+    if line.starts_with("---PROFILEDATA---") {
+      columns = None;
+      continue;
+    }
+    if line.starts_with("Flags,") {
+      let header: Vec<&str> = line.split(',').collect();
+      let index = |name: &str| header.iter().position(|h| *h == name);
+      columns = match (index("Flags"), index("IntendedVsync"), index("FrameCompleted")) {
+        (Some(flags), Some(vsync), Some(completed)) => {
+          Some((flags, index("FrameTimelineVsyncId"), vsync, completed))
+        }
+        _ => None,
+      };
+      continue;
+    }
+
+    let Some((flags, vsync_id, vsync, completed)) = columns else {
+      continue;
+    };
+    // 部分列可能为 -1，按 0 处理，保证列下标不偏移
+    let values: Vec<u64> = line.split(',').map(|v| v.trim().parse().unwrap_or(0)).collect();
+    let (Some(&flag), Some(&intended_vsync), Some(&frame_completed)) =
+      (values.get(flags), values.get(vsync), values.get(completed))
+    else {
+      continue;
+    };
+    if flag != 0 || intended_vsync == 0 || frame_completed < intended_vsync {
+      continue;
+    }
+
+    frames.push(FrameTiming {
+      vsync_id: vsync_id
+        .and_then(|i| values.get(i).copied())
+        .filter(|id| *id > 0)
+        .unwrap_or(intended_vsync),
+      intended_vsync,
+      frame_completed,
     });
   }
 
-  // 计算平均帧时间（基于90th百分位数，如果没有则使用默认值）
-  let avg_frame_time = percentile_90th.unwrap_or(1000.0 / fps); // 如果没有百分位数据，用FPS计算
+  frames.sort_by_key(|f| f.vsync_id);
+  frames.dedup_by_key(|f| f.vsync_id);
+  frames
+}
 
-  // 使用卡顿帧数作为 jank_count
-  let jank_count = janky_frames.unwrap_or(0);
+/// 按 PerfDog 的定义统计卡顿：帧间隔大于前 3 帧平均值的 2 倍，且大于 84ms（Jank）/ 125ms（BigJank）
+///
+/// `recent` 为上次采样末尾的最近 3 个帧间隔，统计完成后更新为本次末尾的 3 个。
+fn classify_jank(recent: &mut Vec<f64>, intervals: &[f64]) -> JankStats {
+  let mut stats = JankStats::default();
+  let mut jank_ms = 0.0;
 
-  // 构造帧时间数组（包含90th和95th百分位数）
-  let mut frame_times = vec![avg_frame_time];
-  if let Some(p95) = percentile_95th {
-    frame_times.push(p95);
+  for &interval in intervals {
+    if recent.len() == 3 {
+      let avg = recent.iter().sum::<f64>() / 3.0;
+      if interval > avg * 2.0 && interval > JANK_MIN_MS {
+        stats.jank += 1;
+        jank_ms += interval;
+        if interval > BIG_JANK_MIN_MS {
+          stats.big_jank += 1;
+        }
+      }
+      recent.remove(0);
+    }
+    recent.push(interval);
+  }
+
+  let total_ms: f64 = intervals.iter().sum();
+  if total_ms > 0.0 {
+    stats.stutter = jank_ms / total_ms * 100.0;
+  }
+  stats
+}
+
+/// 最近秩法求百分位数
+fn percentile(values: &[f64], p: f64) -> Option<f64> {
+  if values.is_empty() {
+    return None;
+  }
+  let mut sorted = values.to_vec();
+  sorted.sort_by(|a, b| a.total_cmp(b));
+  let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+  Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// 从 `dumpsys display` 读取屏幕当前刷新率，支持 90/120/144Hz 等高刷及动态刷新率
+fn fetch_refresh_rate(device_id: &str) -> Result<f64> {
+  let cached = REFRESH_RATE_CACHE.lock().ok().and_then(|cache| cache.get(device_id).copied());
+  if let Some((rate, read_at)) = cached {
+    if read_at.elapsed() < REFRESH_RATE_TTL {
+      return Ok(rate);
+    }
+  }
+
+  let raw = run_device(device_id, &["shell", "dumpsys", "display"])?;
+  let rate =
+    parse_refresh_rate(&raw).ok_or_else(|| AdbError::ParseFailed("未读取到屏幕刷新率".into()))?;
+  if let Ok(mut cache) = REFRESH_RATE_CACHE.lock() {
+    cache.insert(device_id.to_string(), (rate, Instant::now()));
+  }
+  Ok(rate)
+}
+
+/// 依次尝试：
+/// - Android 12+：`mActiveSfDisplayMode=DisplayMode{id=1, ..., refreshRate=120.00001, ...}`
+/// - Android 14+：DisplayInfo 中的 `renderFrameRate 120.0`
+/// - 更早版本：DisplayInfo 中的 `modeId 2` 与 `supportedModes [{id=2, ..., fps=90.0}]`
+fn parse_refresh_rate(raw: &str) -> Option<f64> {
+  let number_after = |text: &str, key: &str| {
+    let (_, rest) = text.split_once(key)?;
+    rest
+      .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+      .next()
+      .and_then(|v| v.parse::<f64>().ok())
+      .filter(|rate| *rate > 0.0)
+  };
+
+  if let Some(rate) = raw
+    .lines()
+    .filter(|line| line.contains("mActiveSfDisplayMode"))
+    .find_map(|line| number_after(line, "refreshRate="))
+  {
+    return Some(rate);
+  }
+
+  let info = raw.lines().find(|line| line.contains("DisplayInfo{"))?;
+  if let Some(rate) = number_after(info, "renderFrameRate ") {
+    return Some(rate);
+  }
+  let mode_id = number_after(info, "modeId ")?;
+  let (_, mode) = info.split_once(&format!("{{id={mode_id}, "))?;
+  number_after(mode.split('}').next()?, "fps=")
+}
+
+/// 通过 SurfaceFlinger 图层的上屏时间戳计算帧率
+fn fetch_fps_surfaceflinger(device_id: &str, package: &str) -> Result<FrameStats> {
+  let raw = run_device(device_id, &["shell", "dumpsys", "SurfaceFlinger", "--list"])?;
+  let layers = candidate_layers(&raw, package);
+  if layers.is_empty() {
+    return Err(AdbError::ParseFailed(format!("未找到 {package} 的 SurfaceFlinger 图层")));
+  }
+
+  // 候选图层中可能有不在绘制的（如被 SurfaceView 覆盖的 Activity 窗口），取第一个有帧的
+  let (layer, timestamps) = layers
+    .into_iter()
+    .find_map(|layer| {
+      let quoted = format!("'{layer}'");
+      let raw = run_device(device_id, &["shell", "dumpsys", "SurfaceFlinger", "--latency", &quoted])
+        .ok()?;
+      let timestamps = parse_sf_latency(&raw);
+      (timestamps.len() >= 2).then_some((layer, timestamps))
+    })
+    .ok_or_else(|| AdbError::ParseFailed("SurfaceFlinger 图层没有帧数据".into()))?;
+
+  let now = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap_or_default()
+    .as_millis() as u64;
+  let key = format!("{device_id}:{package}");
+  let prev = SF_FRAME_HISTORY.lock().ok().and_then(|history| history.get(&key).cloned());
+  let last_present = timestamps[timestamps.len() - 1];
+
+  // 缓冲区只保留最近 127 帧，与上次采样有交集时以上次最后一帧为起点
+  let (window, elapsed_ms) = match prev {
+    Some(ref prev) if timestamps[0] <= prev.last_present && now > prev.timestamp => {
+      let window: Vec<u64> = std::iter::once(prev.last_present)
+        .chain(timestamps.iter().copied().filter(|t| *t > prev.last_present))
+        .collect();
+      (window, Some((now - prev.timestamp) as f64))
+    }
+    _ => (timestamps, None),
+  };
+
+  let frame_times: Vec<f64> = window
+    .windows(2)
+    .map(|pair| (pair[1] - pair[0]) as f64 / 1_000_000.0)
+    .collect();
+  let span_ms: f64 = frame_times.iter().sum();
+  let avg_frame_time = if frame_times.is_empty() {
+    0.0
+  } else {
+    span_ms / frame_times.len() as f64
+  };
+  // 有基准时按两次采样的间隔计算，画面静止的时间也计入；否则只能按缓冲区内的帧跨度估算
+  let fps = match elapsed_ms {
+    Some(elapsed) => frame_times.len() as f64 * 1000.0 / elapsed,
+    None if span_ms > 0.0 => frame_times.len() as f64 * 1000.0 / span_ms,
+    None => 0.0,
+  };
+
+  let mut recent_intervals = prev.map(|p| p.recent_intervals).unwrap_or_default();
+  let jank = classify_jank(&mut recent_intervals, &frame_times);
+  if let Ok(mut history) = SF_FRAME_HISTORY.lock() {
+    let entry = SfFrameHistory {
+      last_present,
+      timestamp: now,
+      recent_intervals,
+    };
+    history.insert(key, entry);
   }
 
   Ok(FrameStats {
     fps,
     avg_frame_time,
+    p90_frame_time: percentile(&frame_times, 90.0),
+    p95_frame_time: percentile(&frame_times, 95.0),
     frame_times,
-    jank_count,
+    jank: jank.jank,
+    big_jank: jank.big_jank,
+    stutter: jank.stutter,
+    layer: Some(layer),
+    reset: None,
   })
 }
 
-fn fetch_power(device_id: &str, package: &str) -> Result<f64> {
-  // 首先尝试获取应用级别的功耗统计
-  if let Ok(raw) = run_device(device_id, &["shell", "dumpsys", "batterystats", package]) {
-    // 解析 batterystats 输出，查找功耗相关信息
-    // 格式通常包含: Estimated power use (mAh): XXX
-    for line in raw.lines() {
-      let line = line.trim();
-      if line.contains("Estimated power use") {
-        // 尝试提取 mAh 值
-        if let Some(ma_str) = line.split(':').nth(1) {
-          if let Some(ma_str) = ma_str.split("mAh").next() {
-            if let Ok(power) = ma_str.trim().parse::<f64>() {
-              return Ok(power);
-            }
-          }
-        }
-      }
-      // 或者查找其他功耗指标
-      if line.contains("power use") || line.contains("Power use") {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        for part in parts {
-          if let Ok(power) = part.parse::<f64>() {
-            return Ok(power);
-          }
-        }
+/// 从 `dumpsys SurfaceFlinger --list` 中挑出应用的图层，SurfaceView 优先
+///
+/// Android 11 之前为 `SurfaceView - com.example/com.example.MainActivity#0`，
+/// 之后为 `SurfaceView[com.example/com.example.MainActivity](BLAST)#0`；
+/// Activity 窗口为 `com.example/com.example.MainActivity#0`。
+fn candidate_layers(raw: &str, package: &str) -> Vec<String> {
+  let activity_prefix = format!("{package}/");
+  let mut surface_views = Vec::new();
+  let mut windows = Vec::new();
+
+  for line in raw.lines().map(str::trim) {
+    if line.starts_with("SurfaceView") && line.contains(&activity_prefix) {
+      // Android 10 上 SurfaceView 还会有一个不绘制的 Background 图层
+      if !line.contains("Background") {
+        surface_views.push(line.to_string());
       }
+    } else if line.starts_with(&activity_prefix) {
+      windows.push(line.to_string());
     }
   }
 
-  // 如果 batterystats 不可用，回退到简单的电池状态查询
-  if let Ok(raw) = run_device(device_id, &["shell", "dumpsys", "battery"]) {
-    // 优先查找电流信息（真正的功耗指标）
-    for line in raw.lines() {
-      let line = line.trim();
-      if line.starts_with("current now:") {
-        if let Some(current_str) = line.split(':').nth(1) {
-          if let Ok(current) = current_str.trim().parse::<f64>() {
-            // 只有当电流不为0时才返回（避免显示无意义的0值）
-            if current.abs() > 100.0 { // 至少100微安
-              // 电流通常是微安，转换为毫安
-              return Ok(current / 1000.0);
-            }
-          }
-        }
-      }
+  // 同类图层中新创建的排在后面，更可能是当前界面
+  surface_views.reverse();
+  windows.reverse();
+  surface_views.extend(windows);
+  surface_views
+}
+
+/// 解析 `--latency` 输出，返回有效帧的实际上屏时间（纳秒，升序）
+///
+/// 第一行为刷新周期，其后每行为 `desiredPresent actualPresent frameReady`；
+/// 尚未上屏的帧为 0 或 i64::MAX。
+fn parse_sf_latency(raw: &str) -> Vec<u64> {
+  let mut timestamps: Vec<u64> = raw
+    .lines()
+    .skip(1)
+    .filter_map(|line| line.split_whitespace().nth(1)?.parse::<u64>().ok())
+    .filter(|t| *t != 0 && *t != i64::MAX as u64)
+    .collect();
+  timestamps.sort_unstable();
+  timestamps.dedup();
+  timestamps
+}
+
+/// 由电池电流与电压计算整机功耗（mW），并累计本次会话的电能
+fn fetch_power(device_id: &str) -> Result<PowerStats> {
+  const NODES: [&str; 3] = ["current_now", "voltage_now", "status"];
+  let paths: Vec<String> = NODES
+    .iter()
+    .map(|n| format!("/sys/class/power_supply/battery/{n}"))
+    .collect();
+  let mut args = vec!["shell", "grep", "-H", "."];
+  args.extend(paths.iter().map(String::as_str));
+  args.extend(["2>/dev/null", "||", "true"]);
+  let raw = run_device(device_id, &args)?;
+
+  let mut current: Option<f64> = None;
+  let mut voltage: Option<f64> = None;
+  let mut status = String::new();
+  for line in raw.lines() {
+    let Some((path, value)) = line.trim().split_once(':') else {
+      continue;
+    };
+    match path.rsplit('/').next().unwrap_or_default() {
+      "current_now" => current = value.trim().parse().ok(),
+      "voltage_now" => voltage = value.trim().parse().ok(),
+      "status" => status = value.trim().to_string(),
+      _ => {}
     }
+  }
 
-    // 如果没有有效的电流数据，不返回电压（电压不是功耗指标）
-    // 让它返回错误，这样前端显示 N/A 更合适
+  let (Some(current), Some(voltage)) = (current, voltage) else {
+    return Err(AdbError::ParseFailed("无法读取电池电流/电压".into()));
+  };
+  let (voltage_mv, voltage_unit) = normalize_voltage(voltage)
+    .ok_or_else(|| AdbError::ParseFailed(format!("电池电压数值异常: {voltage}")))?;
+  let (current_ma, current_unit) =
+    normalize_current(device_id, current, &status, Some(voltage_unit));
+  let power_mw = current_ma * voltage_mv / 1000.0;
+  let charging = matches!(status.as_str(), "Charging" | "Full" | "Not charging");
+
+  // 梯形法累计电能，首次采样从 0 开始
+  let now = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap_or_default()
+    .as_millis() as u64;
+  let mut energy_mwh = 0.0;
+  if let Ok(mut history) = POWER_HISTORY.lock() {
+    if let Some(prev) = history.get(device_id) {
+      let hours = now.saturating_sub(prev.timestamp) as f64 / 3_600_000.0;
+      energy_mwh = prev.energy_mwh + (prev.power_mw + power_mw) / 2.0 * hours;
+    }
+    history.insert(device_id.to_string(), PowerHistory { power_mw, energy_mwh, timestamp: now });
   }
 
-  // 如果都无法获取，返回 None 表示数据不可用
-  Err(AdbError::ParseFailed("无法获取功耗数据".into()))
+  Ok(PowerStats {
+    power_mw,
+    current_ma,
+    voltage_mv,
+    current_unit,
+    voltage_unit,
+    energy_mwh,
+    charging,
+  })
+}
+
+/// 换算为 mA，放电为正、充电为负
+///
+/// power_supply ABI 规定 current_now 为 µA、voltage_now 为 µV，少数内核两者都改用 mA/mV，
+/// 因此已知电压单位时电流单位随之确定。不知道电压单位时（如 dumpsys battery），
+/// 沿用该设备之前确定的单位；都没有时绝对值不小于 20000 按 µA 处理，并记住该设备为 µA。
+/// 正负号约定也因厂商而异，以充电状态为准。
+fn normalize_current(
+  device_id: &str,
+  raw: f64,
+  status: &str,
+  voltage_unit: Option<VoltageUnit>,
+) -> (f64, CurrentUnit) {
+  let detected = match voltage_unit {
+    Some(VoltageUnit::Microvolt) => Some(CurrentUnit::Microamp),
+    Some(VoltageUnit::Millivolt) => Some(CurrentUnit::Milliamp),
+    None => (raw.abs() >= 20_000.0).then_some(CurrentUnit::Microamp),
+  };
+  let unit = match CURRENT_UNIT.lock() {
+    Ok(mut units) => match detected {
+      Some(unit) => {
+        units.insert(device_id.to_string(), unit);
+        unit
+      }
+      None => units.get(device_id).copied().unwrap_or(CurrentUnit::Milliamp),
+    },
+    Err(_) => detected.unwrap_or(CurrentUnit::Milliamp),
+  };
+
+  let ma = match unit {
+    CurrentUnit::Microamp => raw.abs() / 1000.0,
+    CurrentUnit::Milliamp => raw.abs(),
+  };
+  let ma = if status == "Charging" { -ma } else { ma };
+  (ma, unit)
+}
+
+/// 换算为 mV；锂电池电压在 3~5V 之间
+fn normalize_voltage(raw: f64) -> Option<(f64, VoltageUnit)> {
+  if raw >= 1_000_000.0 {
+    Some((raw / 1000.0, VoltageUnit::Microvolt))
+  } else if raw >= 1000.0 {
+    Some((raw, VoltageUnit::Millivolt))
+  } else {
+    None
+  }
 }
 
 fn fetch_battery(device_id: &str) -> Result<BatteryStats> {
   let raw = run_device(device_id, &["shell", "dumpsys", "battery"])?;
-  let mut level: Option<f64> = None;
-  let mut temp_c: Option<f64> = None;
+  let mut stats = BatteryStats {
+    level: None,
+    temp_c: None,
+    voltage_mv: None,
+    current_ma: None,
+    status: None,
+    plugged: None,
+    health: None,
+    charge_counter_mah: None,
+  };
 
   for line in raw.lines() {
-    let line = line.trim();
-    if let Some(rest) = line.strip_prefix("level:") {
-      level = rest.trim().parse::<f64>().ok();
-    } else if let Some(rest) = line.strip_prefix("temperature:") {
-      if let Ok(raw_temp) = rest.trim().parse::<f64>() {
-        temp_c = Some(raw_temp / 10.0);
-      }
+    let Some((key, value)) = line.trim().split_once(':') else {
+      continue;
+    };
+    let value = value.trim();
+    match key {
+      "level" => stats.level = value.parse::<f64>().ok(),
+      "temperature" => stats.temp_c = value.parse::<f64>().ok().map(|t| t / 10.0),
+      "voltage" => stats.voltage_mv = value.parse::<f64>().ok(),
+      "status" => stats.status = value.parse::<u32>().ok().map(BatteryStatus::from_code),
+      "health" => stats.health = value.parse::<u32>().ok().map(BatteryHealth::from_code),
+      // 单位为 µAh
+      "Charge counter" => stats.charge_counter_mah = value.parse::<f64>().ok().map(|c| c / 1000.0),
+      "AC powered" if value == "true" => stats.plugged = Some(BatteryPlugged::Ac),
+      "USB powered" if value == "true" => stats.plugged = Some(BatteryPlugged::Usb),
+      "Wireless powered" if value == "true" => stats.plugged = Some(BatteryPlugged::Wireless),
+      "Dock powered" if value == "true" => stats.plugged = Some(BatteryPlugged::Dock),
+      // 部分机型才有，单位与 current_now 节点一致
+      "current now" => stats.current_ma = value.parse::<f64>().ok(),
+      _ => {}
     }
   }
 
-  if level.is_none() && temp_c.is_none() {
+  if stats.level.is_none() && stats.temp_c.is_none() {
     return Err(AdbError::ParseFailed("未获取到电池信息".into()));
   }
 
-  Ok(BatteryStats { level, temp_c })
+  let status = match stats.status {
+    Some(BatteryStatus::Charging) => "Charging",
+    _ => "",
+  };
+  stats.current_ma = stats.current_ma.map(|c| normalize_current(device_id, c, status, None).0);
+  Ok(stats)
 }
 
 fn fetch_traffic(device_id: &str, pid: &str) -> Result<TrafficStats> {
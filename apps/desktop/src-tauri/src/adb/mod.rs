@@ -1,15 +1,26 @@
 use serde::{Deserialize, Serialize};
 
 pub mod app;
+pub mod arsc;
 pub mod command;
 pub mod device;
 pub mod error;
+pub mod icon;
+pub mod meminfo;
 pub mod metrics;
+pub mod startup;
+pub mod thermal;
 
-pub use app::list_apps;
+pub use app::{
+  clear_app_data, current_foreground, force_stop_app, install_apk, kill_background, launch_app,
+  list_app_ops, list_apps, list_permissions, refresh_apps, set_app_op, set_permission,
+  uninstall_app, AppFilter, AppOpMode, InstallOptions,
+};
 pub use command::{set_adb_path, set_bundled_adb_path};
-pub use device::list_devices;
-pub use metrics::{collect_metrics, MetricKey, MetricsSnapshot};
+pub use device::{list_devices, list_users};
+pub use icon::{app_icon, AppIcon};
+pub use metrics::{collect_metrics, reset_session, CollectOptions, MetricKey, MetricsSnapshot};
+pub use startup::{measure_startup, StartupKind, StartupReport, StartupRun};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceInfo {
@@ -18,6 +29,15 @@ pub struct DeviceInfo {
   pub state: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserInfo {
+  pub id: u32,
+  pub name: String,
+  /// UserInfo 中的十六进制标志位，例如工作资料为 0x1030
+  pub flags: u32,
+  pub running: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppInfo {
   pub package: String,
@@ -27,4 +47,23 @@ pub struct AppInfo {
   pub is_system: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForegroundApp {
+  pub package: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub activity: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionInfo {
+  pub name: String,
+  pub granted: bool,
+  /// 是否为运行时（dangerous）权限，只有运行时权限可以 grant/revoke
+  pub runtime: bool,
+}
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppOpInfo {
+  pub op: String,
+  pub mode: String,
+}
@@ -0,0 +1,204 @@
+use crate::adb::{
+  app::{force_stop_app, push_user, resolve_launcher_activity},
+  command::run_device,
+  error::{AdbError, Result},
+};
+use serde::{Deserialize, Serialize};
+use std::{thread, time::Duration};
+
+// 每次启动前后的等待时间，给系统留出回收进程/绘制完成的时间
+const SETTLE_BEFORE_LAUNCH: Duration = Duration::from_millis(1500);
+const SETTLE_AFTER_LAUNCH: Duration = Duration::from_millis(2000);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupKind {
+  /// 冷启动：进程被杀死后重新拉起
+  Cold,
+  /// 温启动：进程仍在，但 Activity 需要重建
+  Warm,
+  /// 热启动：进程与 Activity 都在后台，直接切回前台
+  Hot,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupRun {
+  pub iteration: u32,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub launch_state: Option<String>,
+  pub total_time_ms: Option<f64>,
+  pub wait_time_ms: Option<f64>,
+  pub this_time_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupStats {
+  pub count: usize,
+  pub min: f64,
+  pub max: f64,
+  pub avg: f64,
+  pub median: f64,
+  pub stddev: f64,
+}
+
+impl StartupStats {
+  fn from_samples(samples: &[f64]) -> Option<Self> {
+    if samples.is_empty() {
+      return None;
+    }
+
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let count = sorted.len();
+    let avg = sorted.iter().sum::<f64>() / count as f64;
+    // 奇数个样本时两个下标相同
+    let median = (sorted[(count - 1) / 2] + sorted[count / 2]) / 2.0;
+    let variance = sorted.iter().map(|v| (v - avg).powi(2)).sum::<f64>() / count as f64;
+
+    Some(StartupStats {
+      count,
+      min: sorted[0],
+      max: sorted[count - 1],
+      avg,
+      median,
+      stddev: variance.sqrt(),
+    })
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupReport {
+  pub package: String,
+  pub kind: StartupKind,
+  pub component: String,
+  pub runs: Vec<StartupRun>,
+  pub total_time: Option<StartupStats>,
+  pub wait_time: Option<StartupStats>,
+  pub this_time: Option<StartupStats>,
+}
+
+/// 按指定方式重复启动应用 `iterations` 次，每完成一次回调 `on_progress`
+pub fn measure_startup(
+  device_id: &str,
+  package: &str,
+  kind: StartupKind,
+  iterations: u32,
+  user_id: Option<u32>,
+  mut on_progress: impl FnMut(&StartupRun),
+) -> Result<StartupReport> {
+  let component = resolve_launcher_activity(device_id, package, user_id)?;
+
+  // 温/热启动需要进程已存在，先正常拉起一次作为预热
+  if !matches!(kind, StartupKind::Cold) {
+    start_and_wait(device_id, &component, user_id)?;
+    thread::sleep(SETTLE_AFTER_LAUNCH);
+  }
+
+  let mut runs = Vec::with_capacity(iterations as usize);
+  for iteration in 1..=iterations {
+    prepare_launch(device_id, package, kind, user_id)?;
+    thread::sleep(SETTLE_BEFORE_LAUNCH);
+
+    let raw = start_and_wait(device_id, &component, user_id)?;
+    let mut run = parse_am_start(&raw)?;
+    run.iteration = iteration;
+    on_progress(&run);
+    runs.push(run);
+
+    thread::sleep(SETTLE_AFTER_LAUNCH);
+  }
+
+  let collect = |f: fn(&StartupRun) -> Option<f64>| runs.iter().filter_map(f).collect::<Vec<_>>();
+  let total_time = StartupStats::from_samples(&collect(|r| r.total_time_ms));
+  let wait_time = StartupStats::from_samples(&collect(|r| r.wait_time_ms));
+  let this_time = StartupStats::from_samples(&collect(|r| r.this_time_ms));
+
+  Ok(StartupReport {
+    package: package.to_string(),
+    kind,
+    component,
+    runs,
+    total_time,
+    wait_time,
+    this_time,
+  })
+}
+
+fn prepare_launch(
+  device_id: &str,
+  package: &str,
+  kind: StartupKind,
+  user_id: Option<u32>,
+) -> Result<()> {
+  match kind {
+    StartupKind::Cold => force_stop_app(device_id, package, user_id),
+    StartupKind::Warm => {
+      press_home(device_id)?;
+      // 让系统回收应用的 UI 资源，下次启动时 Activity 需要重建
+      let user = user_id.map(|id| id.to_string());
+      let mut args = vec!["shell", "am", "send-trim-memory"];
+      push_user(&mut args, &user);
+      args.extend([package, "COMPLETE"]);
+      run_device(device_id, &args)?;
+      Ok(())
+    }
+    StartupKind::Hot => press_home(device_id),
+  }
+}
+
+fn press_home(device_id: &str) -> Result<()> {
+  run_device(device_id, &["shell", "input", "keyevent", "KEYCODE_HOME"])?;
+  Ok(())
+}
+
+fn start_and_wait(device_id: &str, component: &str, user_id: Option<u32>) -> Result<String> {
+  let user = user_id.map(|id| id.to_string());
+  let mut args = vec!["shell", "am", "start", "-W"];
+  push_user(&mut args, &user);
+  args.extend(["-n", component]);
+  run_device(device_id, &args)
+}
+
+fn parse_am_start(raw: &str) -> Result<StartupRun> {
+  let mut run = StartupRun {
+    iteration: 0,
+    launch_state: None,
+    total_time_ms: None,
+    wait_time_ms: None,
+    this_time_ms: None,
+  };
+
+  // 输出示例：
+  // Status: ok
+  // LaunchState: COLD
+  // Activity: com.example/.MainActivity
+  // TotalTime: 512
+  // WaitTime: 530
+  for line in raw.lines() {
+    let line = line.trim();
+    if line.starts_with("Error") {
+      return Err(AdbError::CommandFailed(line.to_string()));
+    }
+
+    let Some((key, value)) = line.split_once(':') else {
+      continue;
+    };
+    let value = value.trim();
+    match key.trim() {
+      "Status" if value != "ok" => {
+        return Err(AdbError::CommandFailed(format!("启动状态异常: {value}")));
+      }
+      "LaunchState" => run.launch_state = Some(value.to_string()),
+      "TotalTime" => run.total_time_ms = value.parse().ok(),
+      "WaitTime" => run.wait_time_ms = value.parse().ok(),
+      "ThisTime" => run.this_time_ms = value.parse().ok(),
+      _ => {}
+    }
+  }
+
+  if run.total_time_ms.is_none() && run.wait_time_ms.is_none() {
+    return Err(AdbError::ParseFailed("未获取到启动耗时".into()));
+  }
+
+  Ok(run)
+}
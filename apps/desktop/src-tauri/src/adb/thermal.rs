@@ -0,0 +1,214 @@
+use crate::adb::{
+  command::run_device,
+  error::{AdbError, Result},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// `dumpsys thermalservice` 中的温控状态（PowerManager.THERMAL_STATUS_*）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThermalStatus {
+  None,
+  Light,
+  Moderate,
+  Severe,
+  Critical,
+  Emergency,
+  Shutdown,
+}
+
+impl ThermalStatus {
+  fn from_code(code: u32) -> Option<Self> {
+    Some(match code {
+      0 => ThermalStatus::None,
+      1 => ThermalStatus::Light,
+      2 => ThermalStatus::Moderate,
+      3 => ThermalStatus::Severe,
+      4 => ThermalStatus::Critical,
+      5 => ThermalStatus::Emergency,
+      6 => ThermalStatus::Shutdown,
+      _ => return None,
+    })
+  }
+}
+
+/// /sys/class/thermal 下的一个温区
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThermalZone {
+  pub zone: u32,
+  pub name: String, // thermal_zone*/type，如 cpu-0-0-usr、battery、skin-therm
+  pub temp_c: f64,
+}
+
+/// Thermal HAL 上报的温度传感器
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThermalSensor {
+  pub name: String,
+  pub kind: u32, // Temperature.TYPE_*，3 为机身（skin）
+  pub temp_c: f64,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub status: Option<ThermalStatus>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThermalStats {
+  /// 整机温控状态，Android 10 以下没有 thermalservice 时为空
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub status: Option<ThermalStatus>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub skin_temp_c: Option<f64>,
+  pub sensors: Vec<ThermalSensor>,
+  pub zones: Vec<ThermalZone>,
+}
+
+// Temperature.TYPE_SKIN
+const TYPE_SKIN: u32 = 3;
+
+/// 读取各温区温度与 thermalservice 的温控状态
+pub(crate) fn fetch_thermal(device_id: &str) -> Result<ThermalStats> {
+  let raw = run_device(
+    device_id,
+    &[
+      "shell",
+      "cd",
+      "/sys/class/thermal",
+      "&&",
+      "grep",
+      "-H",
+      ".",
+      "thermal_zone*/type",
+      "thermal_zone*/temp",
+      "2>/dev/null",
+      "||",
+      "true",
+    ],
+  )?;
+  let mut stats = ThermalStats {
+    zones: parse_thermal_zones(&raw),
+    ..Default::default()
+  };
+
+  if let Ok(raw) = run_device(device_id, &["shell", "dumpsys", "thermalservice"]) {
+    parse_thermalservice(&raw, &mut stats);
+  }
+
+  if stats.zones.is_empty() && stats.sensors.is_empty() && stats.status.is_none() {
+    return Err(AdbError::ParseFailed("未读取到温度信息".into()));
+  }
+  Ok(stats)
+}
+
+/// 解析 `grep -H . thermal_zone*/type thermal_zone*/temp` 的输出
+///
+/// 温度通常为毫摄氏度，少数内核直接输出摄氏度；读不到温度的温区（如已关闭）不返回。
+fn parse_thermal_zones(raw: &str) -> Vec<ThermalZone> {
+  let mut names: HashMap<u32, String> = HashMap::new();
+  let mut temps: HashMap<u32, f64> = HashMap::new();
+
+  for line in raw.lines() {
+    // thermal_zone12/temp:41200
+    let Some((path, value)) = line.trim().split_once(':') else {
+      continue;
+    };
+    let Some((dir, file)) = path.split_once('/') else {
+      continue;
+    };
+    let Some(zone) = dir.strip_prefix("thermal_zone").and_then(|z| z.parse::<u32>().ok()) else {
+      continue;
+    };
+    match file {
+      "type" => {
+        names.insert(zone, value.trim().to_string());
+      }
+      "temp" => {
+        if let Ok(temp) = value.trim().parse::<f64>() {
+          let temp_c = if temp.abs() >= 1000.0 { temp / 1000.0 } else { temp };
+          temps.insert(zone, temp_c);
+        }
+      }
+      _ => {}
+    }
+  }
+
+  let mut zones: Vec<ThermalZone> = temps
+    .into_iter()
+    .map(|(zone, temp_c)| ThermalZone {
+      zone,
+      name: names.remove(&zone).unwrap_or_else(|| format!("thermal_zone{zone}")),
+      temp_c,
+    })
+    .collect();
+  zones.sort_by_key(|z| z.zone);
+  zones
+}
+
+/// 解析 `dumpsys thermalservice`：
+///
+/// ```text
+/// Thermal Status: 1
+/// Cached temperatures:
+///   Temperature{mValue=33.2, mType=3, mName=skin, mStatus=0}
+/// Current temperatures from HAL:
+///   Temperature{mValue=45.1, mType=0, mName=CPU0, mStatus=1}
+/// ```
+///
+/// 优先使用 HAL 的实时温度，没有时回退到缓存的温度。
+fn parse_thermalservice(raw: &str, stats: &mut ThermalStats) {
+  let mut cached = Vec::new();
+  let mut current = Vec::new();
+  let mut in_current = false;
+
+  for line in raw.lines() {
+    let line = line.trim();
+    if let Some(code) = line.strip_prefix("Thermal Status:") {
+      stats.status = code.trim().parse::<u32>().ok().and_then(ThermalStatus::from_code);
+      continue;
+    }
+    if line.ends_with(':') {
+      in_current = line.starts_with("Current temperatures");
+      continue;
+    }
+    let Some(sensor) = parse_temperature(line) else {
+      continue;
+    };
+    if in_current {
+      current.push(sensor);
+    } else {
+      cached.push(sensor);
+    }
+  }
+
+  stats.sensors = if current.is_empty() { cached } else { current };
+  stats.skin_temp_c = stats
+    .sensors
+    .iter()
+    .filter(|s| s.kind == TYPE_SKIN)
+    .map(|s| s.temp_c)
+    .reduce(f64::max);
+}
+
+// Temperature{mValue=45.1, mType=0, mName=CPU0, mStatus=1}
+fn parse_temperature(line: &str) -> Option<ThermalSensor> {
+  let body = line.strip_prefix("Temperature{")?.strip_suffix('}')?;
+  let mut value = None;
+  let mut kind = None;
+  let mut name = None;
+  let mut status = None;
+  for (key, val) in body.split(", ").filter_map(|field| field.split_once('=')) {
+    match key {
+      "mValue" => value = val.parse::<f64>().ok(),
+      "mType" => kind = val.parse::<u32>().ok(),
+      "mName" => name = Some(val.to_string()),
+      "mStatus" => status = val.parse::<u32>().ok().and_then(ThermalStatus::from_code),
+      _ => {}
+    }
+  }
+
+  Some(ThermalSensor {
+    name: name?,
+    kind: kind.unwrap_or_default(),
+    temp_c: value?,
+    status,
+  })
+}
@@ -1,17 +1,24 @@
 use crate::adb::{
   command::{run_device, run_host},
-  collect_metrics, list_apps, list_devices, set_adb_path, AppInfo, DeviceInfo, MetricKey,
-  MetricsSnapshot,
+  error::{AdbError, InstallFailure},
+  app_icon, clear_app_data, collect_metrics, current_foreground, force_stop_app, install_apk,
+  kill_background, launch_app, list_app_ops, list_apps, list_devices, list_permissions, list_users,
+  measure_startup, refresh_apps, reset_session, set_adb_path, set_app_op, set_permission,
+  uninstall_app, AppFilter, AppIcon, AppInfo, AppOpInfo, AppOpMode, CollectOptions, DeviceInfo,
+  ForegroundApp, InstallOptions, MetricKey, MetricsSnapshot, PermissionInfo, StartupKind,
+  StartupReport, StartupRun, UserInfo,
 };
-use log::{error, info};
-use serde::Deserialize;
-use tauri::async_runtime::spawn_blocking;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime::spawn_blocking, AppHandle, Emitter, Manager};
 
 #[derive(Debug, Deserialize)]
 pub struct ListAppsPayload {
   pub device_id: String,
+  #[serde(flatten)]
+  pub filter: AppFilter,
   #[serde(default)]
-  pub keyword: Option<String>,
+  pub user_id: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -19,6 +26,10 @@ pub struct MetricsPayload {
   pub device_id: String,
   pub package: String,
   pub metrics: Vec<MetricKey>,
+  #[serde(default)]
+  pub user_id: Option<u32>,
+  #[serde(default)]
+  pub options: CollectOptions,
 }
 
 #[tauri::command]
@@ -44,9 +55,32 @@ pub async fn tauri_list_devices() -> Result<Vec<DeviceInfo>, String> {
   .map_err(|e| format!("ADB错误: {}", e.to_string()))
 }
 
+#[tauri::command]
+pub async fn tauri_list_users(device_id: String) -> Result<Vec<UserInfo>, String> {
+  spawn_blocking(move || list_users(&device_id))
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn tauri_list_apps(payload: ListAppsPayload) -> Result<Vec<AppInfo>, String> {
-  spawn_blocking(move || list_apps(&payload.device_id, payload.keyword.as_deref()))
+  spawn_blocking(move || list_apps(&payload.device_id, &payload.filter, payload.user_id))
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefreshAppsPayload {
+  pub device_id: String,
+  #[serde(default)]
+  pub user_id: Option<u32>,
+}
+
+#[tauri::command]
+pub async fn tauri_refresh_apps(payload: RefreshAppsPayload) -> Result<usize, String> {
+  spawn_blocking(move || refresh_apps(&payload.device_id, payload.user_id))
     .await
     .map_err(|e| e.to_string())?
     .map_err(|e| e.to_string())
@@ -54,12 +88,310 @@ pub async fn tauri_list_apps(payload: ListAppsPayload) -> Result<Vec<AppInfo>, S
 
 #[tauri::command]
 pub async fn tauri_get_metrics(payload: MetricsPayload) -> Result<MetricsSnapshot, String> {
-  spawn_blocking(move || collect_metrics(&payload.device_id, &payload.package, &payload.metrics))
+  spawn_blocking(move || {
+    collect_metrics(
+      &payload.device_id,
+      &payload.package,
+      &payload.metrics,
+      payload.user_id,
+      &payload.options,
+    )
+  })
+  .await
+  .map_err(|e| e.to_string())?
+  .map_err(|e| e.to_string())
+}
+
+/// 开始一次新的采集前调用，清空上一次会话的跟随状态等基准数据
+#[tauri::command]
+pub async fn tauri_reset_metrics_session(device_id: String, package: String) -> Result<(), String> {
+  spawn_blocking(move || reset_session(&device_id, &package))
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn tauri_current_foreground(device_id: String) -> Result<ForegroundApp, String> {
+  spawn_blocking(move || current_foreground(&device_id))
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AppPayload {
+  pub device_id: String,
+  pub package: String,
+  #[serde(default)]
+  pub user_id: Option<u32>,
+}
+
+#[tauri::command]
+pub async fn tauri_launch_app(payload: AppPayload) -> Result<(), String> {
+  spawn_blocking(move || launch_app(&payload.device_id, &payload.package, payload.user_id))
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn tauri_force_stop_app(payload: AppPayload) -> Result<(), String> {
+  spawn_blocking(move || force_stop_app(&payload.device_id, &payload.package, payload.user_id))
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn tauri_clear_app_data(payload: AppPayload) -> Result<(), String> {
+  spawn_blocking(move || clear_app_data(&payload.device_id, &payload.package, payload.user_id))
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn tauri_kill_app_background(payload: AppPayload) -> Result<(), String> {
+  spawn_blocking(move || kill_background(&payload.device_id, &payload.package, payload.user_id))
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AppIconPayload {
+  pub device_id: String,
+  pub package: String,
+  /// 同时返回 data URL，便于直接用于 <img src>
+  #[serde(default)]
+  pub data_url: bool,
+  #[serde(default)]
+  pub user_id: Option<u32>,
+}
+
+#[tauri::command]
+pub async fn tauri_get_app_icon(
+  app: AppHandle,
+  payload: AppIconPayload,
+) -> Result<AppIcon, String> {
+  let cache_dir = app.path().app_cache_dir().map_err(|e| e.to_string())?;
+  spawn_blocking(move || {
+    app_icon(
+      &payload.device_id,
+      &payload.package,
+      &cache_dir,
+      payload.data_url,
+      payload.user_id,
+    )
+  })
+  .await
+  .map_err(|e| e.to_string())?
+  .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InstallApkPayload {
+  pub device_id: String,
+  pub apk_paths: Vec<String>,
+  #[serde(default)]
+  pub options: InstallOptions,
+  #[serde(default)]
+  pub user_id: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallStage {
+  Installing,
+  Success,
+  Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallProgress {
+  pub stage: InstallStage,
+  pub apk_count: usize,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub message: Option<String>,
+  /// 安装失败且能识别出 `INSTALL_FAILED_*` 失败码时提供
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub failure: Option<InstallFailure>,
+}
+
+#[tauri::command]
+pub async fn tauri_install_apk(app: AppHandle, payload: InstallApkPayload) -> Result<(), String> {
+  spawn_blocking(move || {
+    let apk_count = payload.apk_paths.len();
+    let emit = |stage: InstallStage, message: Option<String>, failure: Option<InstallFailure>| {
+      let progress = InstallProgress {
+        stage,
+        apk_count,
+        message,
+        failure,
+      };
+      if let Err(e) = app.emit("install-progress", progress) {
+        warn!("安装进度推送失败: {}", e);
+      }
+    };
+
+    emit(InstallStage::Installing, None, None);
+    let result = install_apk(
+      &payload.device_id,
+      &payload.apk_paths,
+      &payload.options,
+      payload.user_id,
+    );
+    match &result {
+      Ok(()) => emit(InstallStage::Success, None, None),
+      Err(e) => {
+        let failure = match e {
+          AdbError::InstallFailed(failure) => Some(failure.clone()),
+          _ => None,
+        };
+        emit(InstallStage::Failed, Some(e.to_string()), failure);
+      }
+    }
+    result
+  })
+  .await
+  .map_err(|e| e.to_string())?
+  .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UninstallAppPayload {
+  pub device_id: String,
+  pub package: String,
+  #[serde(default)]
+  pub keep_data: bool,
+  #[serde(default)]
+  pub user_id: Option<u32>,
+}
+
+#[tauri::command]
+pub async fn tauri_uninstall_app(payload: UninstallAppPayload) -> Result<(), String> {
+  spawn_blocking(move || {
+    uninstall_app(&payload.device_id, &payload.package, payload.keep_data, payload.user_id)
+  })
+  .await
+  .map_err(|e| e.to_string())?
+  .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn tauri_list_permissions(payload: AppPayload) -> Result<Vec<PermissionInfo>, String> {
+  spawn_blocking(move || list_permissions(&payload.device_id, &payload.package, payload.user_id))
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetPermissionPayload {
+  pub device_id: String,
+  pub package: String,
+  pub permission: String,
+  pub grant: bool,
+  #[serde(default)]
+  pub user_id: Option<u32>,
+}
+
+#[tauri::command]
+pub async fn tauri_set_permission(payload: SetPermissionPayload) -> Result<(), String> {
+  spawn_blocking(move || {
+    set_permission(
+      &payload.device_id,
+      &payload.package,
+      &payload.permission,
+      payload.grant,
+      payload.user_id,
+    )
+  })
+  .await
+  .map_err(|e| e.to_string())?
+  .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn tauri_list_app_ops(payload: AppPayload) -> Result<Vec<AppOpInfo>, String> {
+  spawn_blocking(move || list_app_ops(&payload.device_id, &payload.package, payload.user_id))
     .await
     .map_err(|e| e.to_string())?
     .map_err(|e| e.to_string())
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SetAppOpPayload {
+  pub device_id: String,
+  pub package: String,
+  pub op: String,
+  pub mode: AppOpMode,
+  #[serde(default)]
+  pub user_id: Option<u32>,
+}
+
+#[tauri::command]
+pub async fn tauri_set_app_op(payload: SetAppOpPayload) -> Result<(), String> {
+  spawn_blocking(move || {
+    set_app_op(
+      &payload.device_id,
+      &payload.package,
+      &payload.op,
+      payload.mode,
+      payload.user_id,
+    )
+  })
+  .await
+  .map_err(|e| e.to_string())?
+  .map_err(|e| e.to_string())
+}
+
+fn default_startup_iterations() -> u32 {
+  5
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StartupPayload {
+  pub device_id: String,
+  pub package: String,
+  pub kind: StartupKind,
+  #[serde(default = "default_startup_iterations")]
+  pub iterations: u32,
+  #[serde(default)]
+  pub user_id: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupProgress {
+  pub iteration: u32,
+  pub total: u32,
+  pub run: StartupRun,
+}
+
+#[tauri::command]
+pub async fn tauri_measure_startup(
+  app: AppHandle,
+  payload: StartupPayload,
+) -> Result<StartupReport, String> {
+  spawn_blocking(move || {
+    let total = payload.iterations;
+    let (device_id, package) = (&payload.device_id, &payload.package);
+    measure_startup(device_id, package, payload.kind, total, payload.user_id, |run| {
+      let progress = StartupProgress {
+        iteration: run.iteration,
+        total,
+        run: run.clone(),
+      };
+      if let Err(e) = app.emit("startup-progress", progress) {
+        warn!("启动耗时进度推送失败: {}", e);
+      }
+    })
+  })
+  .await
+  .map_err(|e| e.to_string())?
+  .map_err(|e| e.to_string())
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ExecuteAdbCommandPayload {
   pub device_id: Option<String>,
@@ -33,8 +33,24 @@ pub fn run() {
     )
     .invoke_handler(tauri::generate_handler![
       commands::tauri_list_devices,
+      commands::tauri_list_users,
       commands::tauri_list_apps,
+      commands::tauri_refresh_apps,
+      commands::tauri_get_app_icon,
       commands::tauri_get_metrics,
+      commands::tauri_reset_metrics_session,
+      commands::tauri_current_foreground,
+      commands::tauri_launch_app,
+      commands::tauri_force_stop_app,
+      commands::tauri_clear_app_data,
+      commands::tauri_kill_app_background,
+      commands::tauri_measure_startup,
+      commands::tauri_install_apk,
+      commands::tauri_uninstall_app,
+      commands::tauri_list_permissions,
+      commands::tauri_set_permission,
+      commands::tauri_list_app_ops,
+      commands::tauri_set_app_op,
       commands::tauri_execute_adb_command,
       commands::tauri_set_adb_path
     ])
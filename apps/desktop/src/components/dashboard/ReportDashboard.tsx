@@ -86,7 +86,7 @@ export function ReportDashboard({ chartData, metrics }: ReportDashboardProps) {
     if (powerValues.length > 0) {
       stats.push({
         metric: "power",
-        label: "功耗 (mA)",
+        label: "功耗 (mW)",
         dataKey: "power",
         avg: powerValues.reduce((a, b) => a + b, 0) / powerValues.length,
         max: Math.max(...powerValues),
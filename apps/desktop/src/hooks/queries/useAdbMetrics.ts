@@ -1,5 +1,5 @@
 import { useCallback, useEffect, useRef } from "react"
-import { getMetrics } from "@/lib/tauri-adb"
+import { getMetrics, resetMetricsSession } from "@/lib/tauri-adb"
 import { useMonitoringStore } from "@/stores/use-monitoring-store"
 import type { MetricKey } from "@/types/adb"
 
@@ -17,8 +17,11 @@ export interface StartMonitorPayload {
 export function useAdbMetrics() {
   const { setMetrics, setMetricsError } = useMonitoringStore()
   const timerRef = useRef<number | null>(null)
+  // 每次 stop 递增，用于丢弃 stop 之后才完成的 start
+  const sessionRef = useRef(0)
 
   const stop = useCallback(() => {
+    sessionRef.current += 1
     if (timerRef.current) {
       window.clearInterval(timerRef.current)
       timerRef.current = null
@@ -43,15 +46,24 @@ export function useAdbMetrics() {
   )
 
   const start = useCallback(
-    (payload: StartMonitorPayload) => {
+    async (payload: StartMonitorPayload) => {
       stop()
+      const session = sessionRef.current
       const interval = payload.intervalMs ?? 1000
+      try {
+        await resetMetricsSession(payload.deviceId, payload.packageName)
+      } catch (err) {
+        setMetricsError(err instanceof Error ? err.message : String(err))
+      }
+      if (session !== sessionRef.current) {
+        return
+      }
       void tick(payload)
       timerRef.current = window.setInterval(() => {
         void tick(payload)
       }, interval)
     },
-    [stop, tick]
+    [stop, tick, setMetricsError]
   )
 
   useEffect(() => {
@@ -1,15 +1,73 @@
 import { invoke } from "@tauri-apps/api/core"
-import type { AdbApp, AdbDevice, MetricKey, MetricsSnapshot } from "@/types/adb"
+import type {
+  AdbApp,
+  AdbDevice,
+  AdbUser,
+  AppIcon,
+  AppKind,
+  AppOpInfo,
+  AppOpMode,
+  CollectOptions,
+  ForegroundApp,
+  InstallOptions,
+  MetricKey,
+  MetricsSnapshot,
+  PermissionInfo,
+  StartupKind,
+  StartupReport,
+} from "@/types/adb"
 
 export async function listDevices() {
   return invoke<AdbDevice[]>("tauri_list_devices")
 }
 
-export async function listApps(deviceId: string, keyword?: string) {
+export async function listUsers(deviceId: string) {
+  return invoke<AdbUser[]>("tauri_list_users", { deviceId })
+}
+
+export interface AppFilter {
+  /** 包名或应用名包含的关键字（应用名在读取图标后才可用） */
+  keyword?: string
+  /** 包名前缀 */
+  prefix?: string
+  kind?: AppKind
+}
+
+export async function listApps(deviceId: string, filter?: AppFilter | string, userId?: number) {
+  const resolved = typeof filter === "string" ? { keyword: filter } : filter
   return invoke<AdbApp[]>("tauri_list_apps", {
     payload: {
       device_id: deviceId,
-      keyword,
+      ...resolved,
+      user_id: userId,
+    },
+  })
+}
+
+/**
+ * 忽略缓存重新拉取应用列表，返回应用数量
+ */
+export async function refreshApps(deviceId: string, userId?: number) {
+  return invoke<number>("tauri_refresh_apps", {
+    payload: {
+      device_id: deviceId,
+      user_id: userId,
+    },
+  })
+}
+
+export async function getAppIcon(
+  deviceId: string,
+  packageName: string,
+  dataUrl = true,
+  userId?: number
+) {
+  return invoke<AppIcon>("tauri_get_app_icon", {
+    payload: {
+      device_id: deviceId,
+      package: packageName,
+      data_url: dataUrl,
+      user_id: userId,
     },
   })
 }
@@ -18,6 +76,8 @@ export interface MetricsPayload {
   deviceId: string
   packageName: string
   metrics: MetricKey[]
+  userId?: number
+  options?: CollectOptions
 }
 
 export async function getMetrics(payload: MetricsPayload) {
@@ -26,6 +86,127 @@ export async function getMetrics(payload: MetricsPayload) {
       device_id: payload.deviceId,
       package: payload.packageName,
       metrics: payload.metrics,
+      user_id: payload.userId,
+      options: payload.options,
+    },
+  })
+}
+
+/**
+ * 开始新的采集会话前调用，清空上一次会话遗留的跟随状态等基准数据
+ */
+export async function resetMetricsSession(deviceId: string, packageName: string) {
+  return invoke<void>("tauri_reset_metrics_session", { deviceId, package: packageName })
+}
+
+export async function getCurrentForeground(deviceId: string) {
+  return invoke<ForegroundApp>("tauri_current_foreground", { deviceId })
+}
+
+export interface AppPayload {
+  deviceId: string
+  packageName: string
+  userId?: number
+}
+
+function toAppPayload(payload: AppPayload) {
+  return {
+    payload: {
+      device_id: payload.deviceId,
+      package: payload.packageName,
+      user_id: payload.userId,
+    },
+  }
+}
+
+export async function launchApp(payload: AppPayload) {
+  return invoke<void>("tauri_launch_app", toAppPayload(payload))
+}
+
+export async function forceStopApp(payload: AppPayload) {
+  return invoke<void>("tauri_force_stop_app", toAppPayload(payload))
+}
+
+export async function clearAppData(payload: AppPayload) {
+  return invoke<void>("tauri_clear_app_data", toAppPayload(payload))
+}
+
+export async function killAppBackground(payload: AppPayload) {
+  return invoke<void>("tauri_kill_app_background", toAppPayload(payload))
+}
+
+export async function installApk(
+  deviceId: string,
+  apkPaths: string[],
+  options?: InstallOptions,
+  userId?: number
+) {
+  return invoke<void>("tauri_install_apk", {
+    payload: {
+      device_id: deviceId,
+      apk_paths: apkPaths,
+      options,
+      user_id: userId,
+    },
+  })
+}
+
+export async function uninstallApp(payload: AppPayload & { keepData?: boolean }) {
+  return invoke<void>("tauri_uninstall_app", {
+    payload: {
+      device_id: payload.deviceId,
+      package: payload.packageName,
+      keep_data: payload.keepData,
+      user_id: payload.userId,
+    },
+  })
+}
+
+export async function listPermissions(payload: AppPayload) {
+  return invoke<PermissionInfo[]>("tauri_list_permissions", toAppPayload(payload))
+}
+
+export async function setPermission(payload: AppPayload & { permission: string; grant: boolean }) {
+  return invoke<void>("tauri_set_permission", {
+    payload: {
+      device_id: payload.deviceId,
+      package: payload.packageName,
+      permission: payload.permission,
+      grant: payload.grant,
+      user_id: payload.userId,
+    },
+  })
+}
+
+export async function listAppOps(payload: AppPayload) {
+  return invoke<AppOpInfo[]>("tauri_list_app_ops", toAppPayload(payload))
+}
+
+export async function setAppOp(payload: AppPayload & { op: string; mode: AppOpMode }) {
+  return invoke<void>("tauri_set_app_op", {
+    payload: {
+      device_id: payload.deviceId,
+      package: payload.packageName,
+      op: payload.op,
+      mode: payload.mode,
+      user_id: payload.userId,
+    },
+  })
+}
+
+export interface StartupPayload extends AppPayload {
+  kind: StartupKind
+  iterations?: number
+}
+
+export async function measureStartup(payload: StartupPayload) {
+  return invoke<StartupReport>("tauri_measure_startup", {
+    payload: {
+      device_id: payload.deviceId,
+      package: payload.packageName,
+      kind: payload.kind,
+      iterations: payload.iterations,
+      user_id: payload.userId,
     },
   })
 }
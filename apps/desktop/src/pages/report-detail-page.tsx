@@ -200,7 +200,7 @@ export function ReportDetailPage({ reportId, onBack }: ReportDetailPageProps) {
                 xKey="time"
                 height={224}
                 lines={[
-                  { dataKey: "power", label: "功耗 (mA)", color: "hsl(16, 90%, 55%)" },
+                  { dataKey: "power", label: "功耗 (mW)", color: "hsl(16, 90%, 55%)" },
                   { dataKey: "battery", label: "电量 (%)", color: "hsl(45, 90%, 55%)" },
                   { dataKey: "battery_temp", label: "温度 (°C)", color: "hsl(10, 80%, 55%)" },
                 ]}
@@ -7,6 +7,12 @@ export type MetricKey =
   | "battery"
   | "battery_temp"
   | "traffic"
+  | "system_cpu"
+  | "thread_cpu"
+  | "cpu_freq"
+  | "memory_pressure"
+  | "gpu"
+  | "thermal"
 
 export interface AdbDevice {
   id: string
@@ -14,6 +20,16 @@ export interface AdbDevice {
   state: string
 }
 
+export interface AdbUser {
+  id: number
+  name: string
+  /** UserInfo 标志位，例如工作资料为 0x1030 */
+  flags: number
+  running: boolean
+}
+
+export type AppKind = "all" | "system" | "third_party"
+
 export interface AdbApp {
   package: string
   label?: string | null
@@ -23,19 +39,258 @@ export interface AdbApp {
   is_system?: boolean
 }
 
+export interface AppIcon {
+  /** 本地缓存的图标文件路径 */
+  path: string
+  mime: string
+  data_url?: string | null
+  /** 从 APK 清单读出的应用名 */
+  label?: string | null
+}
+
+export interface ForegroundApp {
+  package: string
+  activity?: string | null
+}
+
+export interface ForegroundSwitch {
+  from?: string | null
+  to: string
+  timestamp: number
+}
+
+export interface CollectOptions {
+  /** 前台应用切换时自动切换采集目标 */
+  follow_foreground?: boolean
+  /** 线程 CPU 指标返回的线程数，默认 10 */
+  thread_top_n?: number
+  /** FPS 数据来源，默认 gfxinfo；游戏、Flutter 等 SurfaceView 应用使用 surface_flinger */
+  fps_source?: FpsSource
+  /** 每次读取 gfxinfo 后 reset，使帧数据只反映本次采样窗口 */
+  gfxinfo_reset?: boolean
+}
+
+export type FpsSource = "gfxinfo" | "surface_flinger"
+
+/** warming_up：首次采样或采样间隔过短，尚无基准 */
+export type FpsStatus = "ok" | "warming_up" | "unavailable"
+
+export interface ProcessMetrics {
+  pid: number
+  name: string
+  uid?: number | null
+  cpu?: number | null
+  cpu_normalized?: number | null
+  memory_mb?: number | null
+}
+
+export interface MemoryBreakdown {
+  pid: number
+  process: string
+  java_heap_mb?: number | null
+  native_heap_mb?: number | null
+  code_mb?: number | null
+  stack_mb?: number | null
+  graphics_mb?: number | null
+  private_other_mb?: number | null
+  system_mb?: number | null
+  total_pss_mb?: number | null
+  total_rss_mb?: number | null
+  total_swap_mb?: number | null
+  views?: number | null
+  view_roots?: number | null
+  activities?: number | null
+  app_contexts?: number | null
+  local_binders?: number | null
+  proxy_binders?: number | null
+  death_recipients?: number | null
+  webviews?: number | null
+}
+
+export interface PsiStat {
+  avg10: number
+  avg60: number
+  avg300: number
+  total_us: number
+}
+
+export interface MemoryPsi {
+  some?: PsiStat | null
+  full?: PsiStat | null
+}
+
+export interface LmkKill {
+  timestamp: number
+  process: string
+  pid?: number | null
+  oom_score_adj?: number | null
+  reason?: string | null
+}
+
+export interface MemoryPressure {
+  total_mb?: number | null
+  available_mb?: number | null
+  cached_mb?: number | null
+  swap_total_mb?: number | null
+  swap_free_mb?: number | null
+  psi?: MemoryPsi | null
+  lmk_kills: LmkKill[]
+}
+
+export type GpuSource = "adreno" | "mali"
+
+export interface GpuStats {
+  source: GpuSource
+  usage?: number | null
+  cur_mhz?: number | null
+  min_mhz?: number | null
+  max_mhz?: number | null
+  governor?: string | null
+}
+
+export type BatteryStatus = "unknown" | "charging" | "discharging" | "not_charging" | "full"
+export type BatteryPlugged = "ac" | "usb" | "wireless" | "dock"
+export type BatteryHealth =
+  | "unknown"
+  | "good"
+  | "overheat"
+  | "dead"
+  | "over_voltage"
+  | "failure"
+  | "cold"
+
+export type CurrentUnit = "microamp" | "milliamp"
+export type VoltageUnit = "microvolt" | "millivolt"
+
+export interface PowerStats {
+  power_mw: number // 放电为正，充电为负
+  current_ma: number // 放电为正，充电为负
+  voltage_mv: number
+  current_unit: CurrentUnit // power_supply 节点原始数值的单位
+  voltage_unit: VoltageUnit
+  energy_mwh: number // 本次会话累计消耗的电能
+  charging: boolean // 接着外部电源，此时功耗数值不代表真实耗电
+}
+
+export type ThermalStatus =
+  | "none"
+  | "light"
+  | "moderate"
+  | "severe"
+  | "critical"
+  | "emergency"
+  | "shutdown"
+
+export interface ThermalZone {
+  zone: number
+  name: string // thermal_zone*/type，如 cpu-0-0-usr、battery、skin-therm
+  temp_c: number
+}
+
+export interface ThermalSensor {
+  name: string
+  kind: number // Temperature.TYPE_*，3 为机身（skin）
+  temp_c: number
+  status?: ThermalStatus | null
+}
+
+export interface ThermalStats {
+  status?: ThermalStatus | null
+  skin_temp_c?: number | null
+  sensors: ThermalSensor[]
+  zones: ThermalZone[]
+}
+
+export type NetworkKind = "wifi" | "cellular" | "other"
+
+export interface InterfaceTraffic {
+  name: string
+  kind: NetworkKind
+  rx_bytes: number
+  tx_bytes: number
+  rx_bps?: number | null // 字节/秒，首次采样或计数器重置时为空
+  tx_bps?: number | null
+}
+
+export interface NetworkStats {
+  rx_bps?: number | null
+  tx_bps?: number | null
+  wifi_rx_bps?: number | null
+  wifi_tx_bps?: number | null
+  cellular_rx_bps?: number | null
+  cellular_tx_bps?: number | null
+  interfaces: InterfaceTraffic[]
+}
+
+export interface CoreUsage {
+  core: number
+  usage: number
+}
+
+export interface CpuFreqInfo {
+  core: number
+  online: boolean
+  cur_khz?: number | null
+  min_khz?: number | null
+  max_khz?: number | null // scaling_max_freq，受温控/省电策略限制
+  hw_max_khz?: number | null // cpuinfo_max_freq，硬件上限
+  governor?: string | null
+}
+
+export interface CpuCluster {
+  id: number
+  cores: number[]
+  cur_khz?: number | null // 簇内在线核心的最高频率
+  max_khz?: number | null
+  hw_max_khz?: number | null
+  governor?: string | null
+}
+
+export interface ThreadCpu {
+  tid: number
+  name: string
+  cpu: number // 以单核为 100%
+}
+
 export interface FrameStats {
   fps: number
   avg_frame_time: number // 平均帧耗时（毫秒）
-  frame_times: number[] // 最近的帧耗时数组
-  jank_count: number // 帧率不稳定的次数
+  frame_times: number[] // 上次采样以来每一帧的耗时（毫秒）
+  p90_frame_time?: number | null
+  p95_frame_time?: number | null
+  jank: number
+  big_jank: number // 同时计入 jank
+  stutter: number // 卡顿时长占比（百分比）
+  layer?: string | null // SurfaceFlinger 来源时实际统计的图层
+  reset?: boolean | null // gfxinfo 来源时，本次读取后是否已 reset 计数
 }
 
 export interface MetricsSnapshot {
+  package?: string | null
+  foreground_switch?: ForegroundSwitch | null
   fps?: number | null
-  cpu?: number | null
-  power?: number | null
-  memory_mb?: number | null
-  network_kbps?: number | null
+  fps_source?: FpsSource | null
+  fps_status?: FpsStatus | null
+  fps_unavailable?: string | null // 无法获取帧率的原因
+  refresh_rate?: number | null // 屏幕当前刷新率（Hz）
+  fps_normalized?: number | null // 帧率占刷新率的百分比
+  cpu?: number | null // 应用全部进程之和，以单核为 100%
+  cpu_normalized?: number | null // 以全部核心为 100%
+  system_cpu?: number | null // 整机 CPU 使用率
+  cpu_cores?: CoreUsage[] | null
+  threads?: ThreadCpu[] | null // 主进程中 CPU 占用最高的线程
+  cpu_freq?: CpuFreqInfo[] | null
+  cpu_clusters?: CpuCluster[] | null
+  gpu?: GpuStats | null
+  gpu_unavailable?: string | null
+  power?: number | null // mW
+  power_stats?: PowerStats | null
+  memory_mb?: number | null // 应用全部进程之和
+  memory_detail?: MemoryBreakdown[] | null
+  memory_pressure?: MemoryPressure | null
+  processes?: ProcessMetrics[] | null
+  network_kbps?: number | null // KB/s
+  network?: NetworkStats | null // 整机各网络接口的速率
   network_bps?: number | null
   rx_bytes?: number | null
   tx_bytes?: number | null
@@ -43,6 +298,92 @@ export interface MetricsSnapshot {
   tx_bps?: number | null
   battery_level?: number | null
   battery_temp_c?: number | null
+  battery_voltage_mv?: number | null
+  battery_current_ma?: number | null // 放电为正，充电为负
+  battery_status?: BatteryStatus | null
+  battery_plugged?: BatteryPlugged | null
+  battery_health?: BatteryHealth | null
+  battery_charge_counter_mah?: number | null
+  charging?: boolean | null // 采样时接着外部电源，功耗相关数据应视为无效
+  thermal?: ThermalStats | null
   frame_stats?: FrameStats | null
   raw?: string | null
 }
+
+export type StartupKind = "cold" | "warm" | "hot"
+
+export interface StartupRun {
+  iteration: number
+  launch_state?: string | null
+  total_time_ms?: number | null
+  wait_time_ms?: number | null
+  this_time_ms?: number | null
+}
+
+export interface StartupStats {
+  count: number
+  min: number
+  max: number
+  avg: number
+  median: number
+  stddev: number
+}
+
+export interface StartupReport {
+  package: string
+  kind: StartupKind
+  component: string
+  runs: StartupRun[]
+  total_time?: StartupStats | null
+  wait_time?: StartupStats | null
+  this_time?: StartupStats | null
+}
+
+/**
+ * 启动耗时测量过程中通过 `startup-progress` 事件推送
+ */
+export interface StartupProgress {
+  iteration: number
+  total: number
+  run: StartupRun
+}
+
+export interface InstallOptions {
+  /** -r：覆盖安装 */
+  replace?: boolean
+  /** -g：授予全部运行时权限 */
+  grant_permissions?: boolean
+  /** -d：允许降级 */
+  allow_downgrade?: boolean
+}
+
+/**
+ * 安装过程中通过 `install-progress` 事件推送
+ */
+export interface InstallFailure {
+  /** 原始失败码，例如 INSTALL_FAILED_VERSION_DOWNGRADE */
+  code: string
+  message: string
+}
+
+export interface InstallProgress {
+  stage: "installing" | "success" | "failed"
+  apk_count: number
+  message?: string | null
+  /** 安装失败且能识别出失败码时提供 */
+  failure?: InstallFailure | null
+}
+
+export interface PermissionInfo {
+  name: string
+  granted: boolean
+  /** 是否为运行时权限，只有运行时权限可以授予/撤销 */
+  runtime: boolean
+}
+
+export type AppOpMode = "allow" | "ignore" | "deny" | "default" | "foreground"
+
+export interface AppOpInfo {
+  op: string
+  mode: string
+}